@@ -0,0 +1,150 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use crate::aabb::AABB;
+use crate::material::{isotropic::Isotropic, volume::Volume, Material};
+use crate::phase::PhaseFunction;
+use crate::rand;
+use crate::ray::Ray;
+use crate::shape::{
+    medium::{Medium, Particles, Segment},
+    HitRecord, Shape,
+};
+use crate::vec3::{Color, Point3};
+
+/// Fog filling the whole scene, without a boundary shape. The density is `density` at `height`
+/// and falls off exponentially with the height above it, or stays the same everywhere when the
+/// falloff is zero. Collisions and transmittance are computed in closed form.
+pub struct Atmosphere {
+    density: f64,
+    falloff: f64,
+    height: f64,
+    material: Rc<dyn Material>,
+}
+
+impl Atmosphere {
+    pub fn new(density: f64, color: Color) -> Self {
+        Self {
+            density,
+            falloff: 0.0,
+            height: 0.0,
+            material: Rc::new(Isotropic::from_color(color)),
+        }
+    }
+
+    pub fn with_phase(density: f64, color: Color, phase: Rc<dyn PhaseFunction>) -> Self {
+        Self {
+            material: Rc::new(Volume::from_color(color, phase)),
+            ..Self::new(density, color)
+        }
+    }
+
+    /// Makes the fog thin out by a factor e every `1 / falloff` units above `height`.
+    pub fn with_height_falloff(mut self, falloff: f64, height: f64) -> Self {
+        self.falloff = falloff;
+        self.height = height;
+        self
+    }
+
+    /// The density at the ray origin, and how fast the density along the ray changes with t.
+    fn density_along(&self, ray: &Ray) -> (f64, f64) {
+        (
+            self.density(ray.origin()),
+            self.falloff * ray.direction().y(),
+        )
+    }
+
+    /// The optical depth along the ray from `t_min` to `t_max`.
+    pub fn optical_depth(&self, ray: &Ray, t_min: f64, t_max: f64) -> f64 {
+        let (origin, rate) = self.density_along(ray);
+        let ray_length = ray.direction().norm();
+        if rate.abs() < 1e-9 {
+            return origin * ray_length * (t_max - t_min);
+        }
+
+        // The integral of the density is its change over the ray, divided by the rate.
+        let density_at = |t: f64| match t {
+            t if t.is_finite() => self.density(ray.at(t)),
+            _ if rate > 0.0 => 0.0,
+            _ => f64::INFINITY,
+        };
+        ray_length * (density_at(t_min) - density_at(t_max)) / rate
+    }
+}
+
+impl Medium for Atmosphere {
+    fn intervals(&self, _: &Ray, t_min: f64, t_max: f64, _: &mut ThreadRng) -> Vec<(f64, f64)> {
+        if self.density > 0.0 && t_min < t_max {
+            vec![(t_min, t_max)]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn collide_in(&self, ray: &Ray, t0: f64, t1: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+        let (origin, rate) = self.density_along(ray);
+        let scale = origin * ray.direction().norm();
+        if scale <= 0.0 {
+            return None;
+        }
+
+        // Invert the optical depth from t0 for a random target depth.
+        let depth = -f64::ln(1.0 - rand(rng));
+        let t = if rate.abs() < 1e-9 {
+            t0 + depth / scale
+        } else {
+            let remaining = f64::exp(-rate * t0) - depth * rate / scale;
+            if remaining <= 0.0 {
+                return None;
+            }
+            -f64::ln(remaining) / rate
+        };
+
+        if t >= t1 {
+            return None;
+        }
+        Some(HitRecord::in_medium(ray, t, Rc::clone(&self.material)))
+    }
+
+    fn transmittance_in(&self, ray: &Ray, t0: f64, t1: f64, _: &mut ThreadRng) -> f64 {
+        f64::exp(-self.optical_depth(ray, t0, t1))
+    }
+}
+
+impl Particles for Atmosphere {
+    fn density(&self, point: Point3) -> f64 {
+        self.density * f64::exp(-self.falloff * (point.y() - self.height))
+    }
+
+    fn material(&self) -> Rc<dyn Material> {
+        Rc::clone(&self.material)
+    }
+}
+
+impl Shape for Atmosphere {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+        self.collide(ray, t_min, t_max, rng)
+    }
+
+    fn bounding_box(&self, _: f64, _: f64) -> Option<Rc<AABB>> {
+        None
+    }
+
+    fn media<'a>(
+        &'a self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut ThreadRng,
+        segments: &mut Vec<Segment<'a>>,
+    ) {
+        for (t0, t1) in self.intervals(ray, t_min, t_max, rng) {
+            segments.push(Segment {
+                t0,
+                t1,
+                particles: self,
+                ray: *ray,
+            });
+        }
+    }
+}
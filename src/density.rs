@@ -0,0 +1,12 @@
+use crate::vec3::Point3;
+
+pub mod grid;
+pub mod turbulence;
+
+/// The density of a heterogeneous participating medium.
+pub trait Density {
+    fn density(&self, point: Point3) -> f64;
+
+    /// An upper bound on `density` everywhere, used as the majorant for delta tracking.
+    fn max_density(&self) -> f64;
+}
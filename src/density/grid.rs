@@ -0,0 +1,190 @@
+use std::fs;
+use std::io;
+
+use super::Density;
+use crate::vec3::Point3;
+
+/// A density given by a voxel grid spanning the box from `minimum` to `maximum`, interpolated
+/// trilinearly between the voxel centers. Outside the box the density is zero.
+pub struct Grid {
+    values: Vec<f64>,
+    resolution: [usize; 3],
+    minimum: Point3,
+    maximum: Point3,
+    max_density: f64,
+}
+
+impl Grid {
+    /// `values` are stored with x varying fastest, then y, then z.
+    pub fn new(values: Vec<f64>, resolution: [usize; 3], minimum: Point3, maximum: Point3) -> Self {
+        assert!(
+            resolution.iter().all(|&r| r > 0),
+            "Grid needs at least one voxel along each axis"
+        );
+        assert_eq!(
+            values.len(),
+            resolution[0] * resolution[1] * resolution[2],
+            "Grid needs one value per voxel"
+        );
+        let max_density = values.iter().cloned().fold(0.0, f64::max);
+        Self {
+            values,
+            resolution,
+            minimum,
+            maximum,
+            max_density,
+        }
+    }
+
+    /// Loads a grid from a raw file: three little endian `u32`s with the resolution along x, y
+    /// and z, followed by one little endian `f32` per voxel, with x varying fastest.
+    pub fn load(filename: &str, minimum: Point3, maximum: Point3) -> io::Result<Self> {
+        let bytes = fs::read(filename)?;
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        if bytes.len() < 12 {
+            return Err(invalid("grid file is too short for its header"));
+        }
+        let read_u32 = |i: usize| {
+            u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]) as usize
+        };
+        let resolution = [read_u32(0), read_u32(4), read_u32(8)];
+        if resolution.contains(&0) {
+            return Err(invalid("grid resolution must not be zero"));
+        }
+        let size = resolution
+            .iter()
+            .try_fold(4, |size: usize, &r| size.checked_mul(r))
+            .and_then(|size| size.checked_add(12))
+            .ok_or_else(|| invalid("grid resolution is too large"))?;
+        if bytes.len() != size {
+            return Err(invalid("grid file size does not match its resolution"));
+        }
+
+        let values = bytes[12..]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64)
+            .collect();
+        Ok(Self::new(values, resolution, minimum, maximum))
+    }
+
+    fn voxel(&self, i: usize, j: usize, k: usize) -> f64 {
+        self.values[(k * self.resolution[1] + j) * self.resolution[0] + i]
+    }
+}
+
+impl Density for Grid {
+    fn density(&self, point: Point3) -> f64 {
+        let mut index = [0; 3];
+        let mut fraction = [0.0; 3];
+        for a in 0..3 {
+            let t = (point[a] - self.minimum[a]) / (self.maximum[a] - self.minimum[a]);
+            if !(0.0..=1.0).contains(&t) {
+                return 0.0;
+            }
+            let x = (t * self.resolution[a] as f64 - 0.5).max(0.0);
+            let i = (x as usize).min(self.resolution[a] - 1);
+            index[a] = i.min(self.resolution[a].saturating_sub(2));
+            fraction[a] = (x - index[a] as f64).min(1.0);
+            if self.resolution[a] == 1 {
+                fraction[a] = 0.0;
+            }
+        }
+
+        let mut density = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let weight = (if di == 1 {
+                        fraction[0]
+                    } else {
+                        1.0 - fraction[0]
+                    }) * (if dj == 1 {
+                        fraction[1]
+                    } else {
+                        1.0 - fraction[1]
+                    }) * (if dk == 1 {
+                        fraction[2]
+                    } else {
+                        1.0 - fraction[2]
+                    });
+                    if weight > 0.0 {
+                        density += weight * self.voxel(index[0] + di, index[1] + dj, index[2] + dk);
+                    }
+                }
+            }
+        }
+        density
+    }
+
+    fn max_density(&self) -> f64 {
+        self.max_density
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A grid file with the given header and `count` values 0, 1, 2, ...
+    fn file(resolution: [u32; 3], count: usize) -> Vec<u8> {
+        let mut bytes: Vec<u8> = resolution.iter().flat_map(|r| r.to_le_bytes()).collect();
+        bytes.extend((0..count).flat_map(|i| (i as f32).to_le_bytes()));
+        bytes
+    }
+
+    fn load(name: &str, bytes: &[u8]) -> io::Result<Grid> {
+        let path = std::env::temp_dir().join(format!("rtc-grid-{}-{}", std::process::id(), name));
+        fs::write(&path, bytes).unwrap();
+        let grid = Grid::load(
+            &path.to_string_lossy(),
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 1.0),
+        );
+        fs::remove_file(&path).unwrap();
+        grid
+    }
+
+    fn error(result: io::Result<Grid>) -> String {
+        match result {
+            Ok(_) => panic!("expected an error"),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn loads_and_interpolates_values() {
+        let grid = load("valid", &file([2, 1, 1], 2)).unwrap();
+        assert_eq!(grid.max_density(), 1.0);
+        assert_eq!(grid.density(Point3::new(0.25, 0.5, 0.5)), 0.0);
+        assert_eq!(grid.density(Point3::new(0.5, 0.5, 0.5)), 0.5);
+        assert_eq!(grid.density(Point3::new(1.5, 0.5, 0.5)), 0.0);
+    }
+
+    #[test]
+    fn rejects_sizes_not_matching_the_resolution() {
+        let message = "grid file size does not match its resolution";
+        assert_eq!(error(load("short", &file([2, 2, 2], 7))), message);
+        assert_eq!(error(load("long", &file([2, 2, 2], 9))), message);
+        assert_eq!(
+            error(load("header", &[1, 0, 0, 0])),
+            "grid file is too short for its header"
+        );
+    }
+
+    #[test]
+    fn rejects_a_zero_resolution() {
+        assert_eq!(
+            error(load("zero", &file([2, 0, 2], 0))),
+            "grid resolution must not be zero"
+        );
+    }
+
+    #[test]
+    fn rejects_an_overflowing_resolution() {
+        assert_eq!(
+            error(load("overflow", &file([u32::MAX; 3], 0))),
+            "grid resolution is too large"
+        );
+    }
+}
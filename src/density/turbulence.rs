@@ -0,0 +1,36 @@
+use rand::rngs::ThreadRng;
+
+use super::Density;
+use crate::perlin::Noise;
+use crate::vec3::Point3;
+
+/// A procedural density from Perlin turbulence, for smoke and clouds.
+pub struct Turbulence {
+    noise: Noise,
+    density: f64,
+    scale: f64,
+    depth: i32,
+}
+
+impl Turbulence {
+    /// The turbulence at `scale * point` is clamped to [0, 1] and multiplied by `density`.
+    pub fn new(density: f64, scale: f64, depth: i32, rng: &mut ThreadRng) -> Self {
+        Self {
+            noise: Noise::new(rng),
+            density,
+            scale,
+            depth,
+        }
+    }
+}
+
+impl Density for Turbulence {
+    fn density(&self, point: Point3) -> f64 {
+        let turbulence = self.noise.turbulence(self.scale * point, self.depth);
+        self.density * turbulence.min(1.0)
+    }
+
+    fn max_density(&self) -> f64 {
+        self.density
+    }
+}
@@ -2,12 +2,22 @@ use rand::rngs::ThreadRng;
 use rand::Rng;
 
 pub mod aabb;
+pub mod atmosphere;
 pub mod camera;
+pub mod density;
+pub mod light;
+pub mod loader;
 pub mod material;
+pub mod matrix;
+pub mod microfacet;
+pub mod onb;
 pub mod perlin;
+pub mod phase;
+pub mod quaternion;
 pub mod ray;
 pub mod scenes;
 pub mod shape;
+pub mod spectrum;
 pub mod texture;
 pub mod vec3;
 
@@ -0,0 +1,51 @@
+use rand::rngs::ThreadRng;
+
+use crate::ray::Ray;
+use crate::shape::{HitRecord, Shape};
+use crate::vec3::{Color, Point3, Vec3};
+
+pub mod area;
+pub mod point;
+
+/// A light that can be sampled directly, instead of only being found by rays that happen to hit
+/// it. Emitting surfaces must also be part of the world.
+pub trait Light {
+    /// Picks a point on the light, with the probability density of picking it per unit area.
+    /// Point lights always return their position with a density of one.
+    fn sample(&self, rng: &mut ThreadRng) -> Option<(Point3, f64)>;
+
+    /// The radiant intensity sent from `point`, picked by `sample`, toward `target`. It is black
+    /// when a surface blocks the way, and media in between block it with a probability equal to
+    /// their opacity.
+    fn intensity(
+        &self,
+        point: Point3,
+        target: Point3,
+        time: f64,
+        world: &dyn Shape,
+        rng: &mut ThreadRng,
+    ) -> Color;
+
+    /// Whether `rec`, where `ray` hit the world, lies on the light, so that sampling the light
+    /// already counted what it emits there. Point lights can't be hit.
+    fn is_hit(&self, _ray: &Ray, _rec: &HitRecord, _rng: &mut ThreadRng) -> bool {
+        false
+    }
+}
+
+/// Samples a distance along the ray between `t0` and `t1` proportionally to the inverse squared
+/// distance to `point`, which is where light from a small light is concentrated. Returns the
+/// ray parameter and its probability density.
+pub fn equiangular(ray: &Ray, point: Point3, t0: f64, t1: f64, u: f64) -> (f64, f64) {
+    let ray_length = ray.direction().norm();
+    let closest = Vec3::dot(point - ray.origin(), ray.direction()) / (ray_length * ray_length);
+    let distance = f64::max((ray.at(closest) - point).norm(), 1e-6);
+
+    let theta0 = f64::atan((t0 - closest) * ray_length / distance);
+    let theta1 = f64::atan((t1 - closest) * ray_length / distance);
+    let offset = distance * f64::tan(theta0 + u * (theta1 - theta0));
+
+    let t = closest + offset / ray_length;
+    let pdf = distance * ray_length / ((theta1 - theta0) * (distance * distance + offset * offset));
+    (t, pdf)
+}
@@ -0,0 +1,54 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::Light;
+use crate::ray::Ray;
+use crate::shape::{HitRecord, Shape};
+use crate::vec3::{Color, Point3, Vec3};
+
+/// An emitting surface, sampled uniformly by area. The light it sends is whatever the material
+/// of the surface emits, so the shape has to be in the world as well.
+pub struct AreaLight {
+    shape: Rc<dyn Shape>,
+}
+
+impl AreaLight {
+    pub fn new(shape: Rc<dyn Shape>) -> Self {
+        Self { shape }
+    }
+}
+
+impl Light for AreaLight {
+    fn sample(&self, rng: &mut ThreadRng) -> Option<(Point3, f64)> {
+        let (point, area) = self.shape.random_point(rng)?;
+        Some((point, 1.0 / area))
+    }
+
+    fn intensity(
+        &self,
+        point: Point3,
+        target: Point3,
+        time: f64,
+        world: &dyn Shape,
+        rng: &mut ThreadRng,
+    ) -> Color {
+        let distance = (point - target).norm();
+        let direction = (point - target) / distance;
+        let shadow = Ray::new(target, direction, time);
+
+        // The first thing the shadow ray hits has to be the light itself.
+        match world.hit(&shadow, 0.001, distance + 0.001, rng) {
+            Some(rec) if rec.t() > distance - 0.001 => {
+                let cosine = Vec3::dot(rec.normal(), direction).abs();
+                rec.material().emitted(&rec) * cosine
+            }
+            _ => Color::default(),
+        }
+    }
+
+    fn is_hit(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> bool {
+        self.shape
+            .hit(ray, rec.t() - 0.001, rec.t() + 0.001, rng)
+            .is_some()
+    }
+}
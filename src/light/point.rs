@@ -0,0 +1,48 @@
+use rand::rngs::ThreadRng;
+
+use super::Light;
+use crate::ray::Ray;
+use crate::shape::Shape;
+use crate::vec3::{Color, Point3};
+
+/// A light in a single point, sending the same intensity in all directions. Rays can't hit it, so
+/// it only lights media and surfaces whose material can be evaluated, like Lambertian ones.
+pub struct PointLight {
+    position: Point3,
+    intensity: Color,
+}
+
+impl PointLight {
+    pub fn new(position: Point3, intensity: Color) -> Self {
+        Self {
+            position,
+            intensity,
+        }
+    }
+
+    pub fn with_watts(position: Point3, color: Color, watts: f64) -> Self {
+        Self::new(position, color * watts / (4.0 * std::f64::consts::PI))
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, _: &mut ThreadRng) -> Option<(Point3, f64)> {
+        Some((self.position, 1.0))
+    }
+
+    fn intensity(
+        &self,
+        point: Point3,
+        target: Point3,
+        time: f64,
+        world: &dyn Shape,
+        rng: &mut ThreadRng,
+    ) -> Color {
+        let distance = (point - target).norm();
+        let shadow = Ray::new(target, (point - target) / distance, time);
+        match world.hit(&shadow, 0.001, distance, rng) {
+            Some(_) => Color::default(),
+            None => self.intensity,
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub mod gltf;
+pub mod obj;
+pub mod ply;
+pub mod stl;
+
+/// An error while loading a model from a file.
+#[derive(Debug)]
+pub enum LoadError {
+    /// A file could not be read.
+    Io { path: PathBuf, source: io::Error },
+    /// A file is malformed, at the given line for text formats.
+    Parse {
+        path: PathBuf,
+        line: Option<usize>,
+        message: String,
+    },
+}
+
+impl LoadError {
+    fn parse(path: &Path, line: Option<usize>, message: impl Into<String>) -> Self {
+        Self::Parse {
+            path: path.to_path_buf(),
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Self::Parse {
+                path,
+                line: Some(line),
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            Self::Parse {
+                path,
+                line: None,
+                message,
+            } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Parse { .. } => None,
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, LoadError> {
+    fs::read(path).map_err(|source| LoadError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn read_to_string(path: &Path) -> Result<String, LoadError> {
+    fs::read_to_string(path).map_err(|source| LoadError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+// The sRGB transfer function, from encoded values to linear ones.
+fn srgb_to_linear(value: f64) -> f64 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// Writes a file for a test into a directory of its own, returning its path.
+#[cfg(test)]
+fn fixture(name: &str, contents: &[u8]) -> String {
+    let directory = std::env::temp_dir().join(format!("rtc-loader-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let path = directory.join(name);
+    fs::write(&path, contents).unwrap();
+    path.to_string_lossy().into_owned()
+}
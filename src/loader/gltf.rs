@@ -0,0 +1,363 @@
+use rand::rngs::ThreadRng;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::path::Path;
+use std::rc::Rc;
+
+use super::{srgb_to_linear, LoadError};
+use crate::camera::CameraConfig;
+use crate::material::{
+    emissive::Emissive,
+    normal_mapped::NormalMapped,
+    principled::{Principled, PrincipledConfig},
+    Material,
+};
+use crate::matrix::Matrix;
+use crate::shape::{
+    shape_list::ShapeList,
+    triangle_mesh::{Mesh, TriangleMesh},
+};
+use crate::texture::{
+    channel::Channel, image::Image, scale::Scale, solid_color::SolidColor, Texture,
+};
+use crate::vec3::{Color, Point3, Vec3};
+
+/// The shapes and cameras of a glTF scene, with the transforms of the node hierarchy applied.
+pub struct Scene {
+    pub world: ShapeList,
+    pub cameras: Vec<CameraConfig>,
+}
+
+/// Loads the default scene of a `.gltf` or `.glb` file. Meshes get principled materials from
+/// the metallic-roughness materials, with their base color, metallic-roughness, normal and
+/// emissive textures, which have to use the first set of uv-coordinates. Only perspective cameras
+/// are loaded.
+pub fn load(filename: &str, rng: &mut ThreadRng) -> Result<Scene, LoadError> {
+    let path = Path::new(filename);
+    let (document, buffers, images) = ::gltf::import(path).map_err(|error| match error {
+        ::gltf::Error::Io(source) => LoadError::Io {
+            path: path.to_path_buf(),
+            source,
+        },
+        error => LoadError::parse(path, None, error.to_string()),
+    })?;
+
+    let scene = document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+        .ok_or_else(|| LoadError::parse(path, None, "the file has no scenes"))?;
+
+    let mut loader = Loader {
+        path,
+        buffers,
+        images,
+        textures: HashMap::new(),
+        materials: HashMap::new(),
+        scene: Scene {
+            world: ShapeList::default(),
+            cameras: Vec::new(),
+        },
+    };
+    for node in scene.nodes() {
+        loader.node(node, Matrix::identity(), rng)?;
+    }
+    Ok(loader.scene)
+}
+
+/// Decodes an image into a texture of linear values. Color images are stored sRGB-encoded, while
+/// data like roughness and normals is stored linearly.
+fn decode(image: &::gltf::image::Data, srgb: bool) -> Rc<dyn Texture> {
+    use ::gltf::image::Format;
+    let (channels, size) = match image.format {
+        Format::R8 => (1, 1),
+        Format::R8G8 => (2, 1),
+        Format::R8G8B8 => (3, 1),
+        Format::R8G8B8A8 => (4, 1),
+        Format::R16 => (1, 2),
+        Format::R16G16 => (2, 2),
+        Format::R16G16B16 => (3, 2),
+        Format::R16G16B16A16 => (4, 2),
+        Format::R32G32B32FLOAT => (3, 4),
+        Format::R32G32B32A32FLOAT => (4, 4),
+    };
+
+    let value = |bytes: &[u8]| {
+        let value = match size {
+            1 => bytes[0] as f64 / u8::MAX as f64,
+            2 => u16::from_ne_bytes(bytes.try_into().unwrap()) as f64 / u16::MAX as f64,
+            _ => f32::from_ne_bytes(bytes.try_into().unwrap()) as f64,
+        };
+        if srgb {
+            srgb_to_linear(value)
+        } else {
+            value
+        }
+    };
+    let pixels = image
+        .pixels
+        .chunks_exact(channels * size)
+        .map(|pixel| {
+            let channel = |i: usize| value(&pixel[i * size..(i + 1) * size]);
+            // Two channels are luminance and alpha, and alpha is ignored.
+            match channels {
+                1 | 2 => Color::new(channel(0), channel(0), channel(0)),
+                _ => Color::new(channel(0), channel(1), channel(2)),
+            }
+        })
+        .collect();
+    Rc::new(Image::from_pixels(
+        pixels,
+        image.width as usize,
+        image.height as usize,
+    ))
+}
+
+struct Loader<'a> {
+    path: &'a Path,
+    buffers: Vec<::gltf::buffer::Data>,
+    images: Vec<::gltf::image::Data>,
+    // By image index and whether the image is sRGB-encoded.
+    textures: HashMap<(usize, bool), Rc<dyn Texture>>,
+    // By material index, with `None` for the default material.
+    materials: HashMap<Option<usize>, Rc<dyn Material>>,
+    scene: Scene,
+}
+
+impl Loader<'_> {
+    fn node(
+        &mut self,
+        node: ::gltf::Node,
+        parent: Matrix,
+        rng: &mut ThreadRng,
+    ) -> Result<(), LoadError> {
+        let local = node
+            .transform()
+            .matrix()
+            .map(|column| column.map(f64::from));
+        let matrix = parent * Matrix::from_columns(local);
+
+        if let Some(mesh) = node.mesh() {
+            for primitive in mesh.primitives() {
+                self.primitive(&primitive, &matrix, rng)?;
+            }
+        }
+
+        if let Some(camera) = node.camera() {
+            if let ::gltf::camera::Projection::Perspective(perspective) = camera.projection() {
+                // Cameras look down their negative z-axis, with y up.
+                let from = matrix.transform_point(Point3::default());
+                let forward = matrix.transform_vector(Vec3::new(0.0, 0.0, -1.0));
+                let defaults = CameraConfig::default();
+                self.scene.cameras.push(CameraConfig {
+                    from,
+                    at: from + forward,
+                    up: matrix.transform_vector(Vec3::new(0.0, 1.0, 0.0)),
+                    vfov: (perspective.yfov() as f64).to_degrees(),
+                    aspect_ratio: perspective
+                        .aspect_ratio()
+                        .map_or(defaults.aspect_ratio, f64::from),
+                    ..defaults
+                });
+            }
+        }
+
+        for child in node.children() {
+            self.node(child, matrix, rng)?;
+        }
+        Ok(())
+    }
+
+    fn primitive(
+        &mut self,
+        primitive: &::gltf::Primitive,
+        matrix: &Matrix,
+        rng: &mut ThreadRng,
+    ) -> Result<(), LoadError> {
+        use ::gltf::mesh::Mode;
+
+        let buffers = &self.buffers;
+        let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+        let positions: Vec<Point3> = reader
+            .read_positions()
+            .ok_or_else(|| LoadError::parse(self.path, None, "a primitive has no positions"))?
+            .map(|[x, y, z]| matrix.transform_point(Point3::new(x as f64, y as f64, z as f64)))
+            .collect();
+        // Normals are transformed by the inverse transpose, and flattened nodes are invisible.
+        let normal_matrix = match matrix.inverse() {
+            Some(inverse) => inverse.transpose(),
+            None => return Ok(()),
+        };
+        let normals = reader.read_normals().map(|normals| {
+            normals
+                .map(|[x, y, z]| {
+                    normal_matrix
+                        .transform_vector(Vec3::new(x as f64, y as f64, z as f64))
+                        .normalized()
+                })
+                .collect()
+        });
+        // glTF puts v = 0 at the top of images, while the textures here put it at the bottom.
+        let uvs = reader.read_tex_coords(0).map(|uvs| {
+            uvs.into_f32()
+                .map(|[u, v]| (u as f64, 1.0 - v as f64))
+                .collect()
+        });
+        let vertices: Vec<usize> = match reader.read_indices() {
+            Some(indices) => indices.into_u32().map(|i| i as usize).collect(),
+            None => (0..positions.len()).collect(),
+        };
+        if let Some(&index) = vertices.iter().find(|&&i| i >= positions.len()) {
+            return Err(LoadError::parse(
+                self.path,
+                None,
+                format!("vertex index {} out of range", index),
+            ));
+        }
+
+        let count = vertices.len();
+        let mut indices: Vec<[usize; 3]> = match primitive.mode() {
+            Mode::Triangles => vertices
+                .chunks_exact(3)
+                .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+                .collect(),
+            Mode::TriangleStrip => (0..count.saturating_sub(2))
+                .map(|i| match i % 2 {
+                    0 => [vertices[i], vertices[i + 1], vertices[i + 2]],
+                    _ => [vertices[i + 1], vertices[i], vertices[i + 2]],
+                })
+                .collect(),
+            Mode::TriangleFan => (1..count.saturating_sub(1))
+                .map(|i| [vertices[0], vertices[i], vertices[i + 1]])
+                .collect(),
+            Mode::Points | Mode::Lines | Mode::LineLoop | Mode::LineStrip => return Ok(()),
+        };
+        if indices.is_empty() {
+            return Ok(());
+        }
+        // A mirroring transform turns the triangles inside out, unless their winding is flipped.
+        if matrix.determinant() < 0.0 {
+            for triangle in &mut indices {
+                triangle.swap(1, 2);
+            }
+        }
+
+        let mesh = Mesh {
+            positions,
+            normals,
+            uvs,
+            colors: None,
+            indices,
+        };
+        let material = self.material(&primitive.material())?;
+        self.scene
+            .world
+            .add(Rc::new(TriangleMesh::new(mesh, material, rng)));
+        Ok(())
+    }
+
+    fn material(&mut self, material: &::gltf::Material) -> Result<Rc<dyn Material>, LoadError> {
+        if let Some(loaded) = self.materials.get(&material.index()) {
+            return Ok(Rc::clone(loaded));
+        }
+
+        let pbr = material.pbr_metallic_roughness();
+        let base_color = self.info(pbr.base_color_texture(), true)?;
+        // Roughness is stored in the green channel and metalness in the blue one.
+        let metallic_roughness = self.info(pbr.metallic_roughness_texture(), false)?;
+        let transmission = material.transmission();
+        let transmission_texture = match &transmission {
+            Some(transmission) => self.info(transmission.transmission_texture(), false)?,
+            None => None,
+        };
+        let emissive = self.info(material.emissive_texture(), true)?;
+        let normal = match material.normal_texture() {
+            Some(normal) => Some((
+                self.texture(normal.texture(), normal.tex_coord(), false)?,
+                normal.scale() as f64,
+            )),
+            None => None,
+        };
+
+        let scaled = |texture: Option<Rc<dyn Texture>>, factor: Color| -> Rc<dyn Texture> {
+            match texture {
+                Some(texture) => Rc::new(Scale::new(texture, factor)),
+                None => Rc::new(SolidColor::new(factor)),
+            }
+        };
+        let channel = |texture: &Option<Rc<dyn Texture>>, channel: usize| {
+            texture
+                .clone()
+                .map(|t| Rc::new(Channel::new(t, channel)) as Rc<dyn Texture>)
+        };
+        let gray = |value: f32| Color::new(value as f64, value as f64, value as f64);
+
+        let [r, g, b, _] = pbr.base_color_factor().map(f64::from);
+        let mut loaded: Rc<dyn Material> = Rc::new(Principled::new(PrincipledConfig {
+            base_color: scaled(base_color, Color::new(r, g, b)),
+            metallic: scaled(channel(&metallic_roughness, 2), gray(pbr.metallic_factor())),
+            roughness: scaled(
+                channel(&metallic_roughness, 1),
+                gray(pbr.roughness_factor()),
+            ),
+            transmission: match &transmission {
+                Some(transmission) => scaled(
+                    channel(&transmission_texture, 0),
+                    gray(transmission.transmission_factor()),
+                ),
+                None => Rc::new(SolidColor::from_value(0.0)),
+            },
+            index_of_refraction: Rc::new(SolidColor::from_value(
+                material.ior().unwrap_or(1.5) as f64
+            )),
+            ..PrincipledConfig::default()
+        }));
+
+        if let Some((normals, scale)) = normal {
+            loaded = Rc::new(NormalMapped::new(loaded, normals, scale));
+        }
+
+        let strength = material.emissive_strength().unwrap_or(1.0) as f64;
+        let [r, g, b] = material.emissive_factor().map(|e| strength * e as f64);
+        if r > 0.0 || g > 0.0 || b > 0.0 {
+            let emission = scaled(emissive, Color::new(r, g, b));
+            loaded = Rc::new(Emissive::new(loaded, emission));
+        }
+
+        self.materials.insert(material.index(), Rc::clone(&loaded));
+        Ok(loaded)
+    }
+
+    fn info(
+        &mut self,
+        info: Option<::gltf::texture::Info>,
+        srgb: bool,
+    ) -> Result<Option<Rc<dyn Texture>>, LoadError> {
+        info.map(|info| self.texture(info.texture(), info.tex_coord(), srgb))
+            .transpose()
+    }
+
+    // Decodes each image once for each encoding it is used with.
+    fn texture(
+        &mut self,
+        texture: ::gltf::Texture,
+        tex_coord: u32,
+        srgb: bool,
+    ) -> Result<Rc<dyn Texture>, LoadError> {
+        // Meshes only keep their first set of uv-coordinates.
+        if tex_coord != 0 {
+            return Err(LoadError::parse(
+                self.path,
+                None,
+                format!("texture coordinate set {} is not supported", tex_coord),
+            ));
+        }
+
+        let index = texture.source().index();
+        let images = &self.images;
+        Ok(Rc::clone(
+            self.textures
+                .entry((index, srgb))
+                .or_insert_with(|| decode(&images[index], srgb)),
+        ))
+    }
+}
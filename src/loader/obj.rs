@@ -0,0 +1,476 @@
+use rand::rngs::ThreadRng;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+
+use super::{read_to_string, LoadError};
+use crate::material::{
+    dielectric::Dielectric, diffuse_light::DiffuseLight, lambertian::Lambertian, metal::Metal,
+    Material,
+};
+use crate::shape::{
+    shape_list::ShapeList,
+    triangle_mesh::{Mesh, TriangleMesh},
+};
+use crate::texture::{image::Image, solid_color::SolidColor, Texture};
+use crate::vec3::{Color, Point3, Vec3};
+
+/// Loads a Wavefront OBJ file, with the materials of its MTL libraries, into one shape per group
+/// or object. Polygons are split into triangles.
+pub fn load(filename: &str, rng: &mut ThreadRng) -> Result<ShapeList, LoadError> {
+    let mut shapes = ShapeList::default();
+    for (_, group) in load_groups(filename, rng)? {
+        shapes.add(Rc::new(group));
+    }
+    Ok(shapes)
+}
+
+/// Loads a Wavefront OBJ file like `load`, keeping the name of each group or object.
+pub fn load_groups(
+    filename: &str,
+    rng: &mut ThreadRng,
+) -> Result<Vec<(String, ShapeList)>, LoadError> {
+    let path = Path::new(filename);
+    let source = read_to_string(path)?;
+
+    let mut positions = Vec::new();
+    let mut uvs = Vec::new();
+    let mut normals = Vec::new();
+    let mut materials = HashMap::new();
+    let mut groups: Vec<Group> = vec![Group::new("default")];
+    let mut current = 0;
+    let mut material: Option<String> = None;
+
+    for (number, line) in source.lines().enumerate() {
+        let number = number + 1;
+        let error = |message: String| LoadError::parse(path, Some(number), message);
+
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) if !keyword.starts_with('#') => keyword,
+            _ => continue,
+        };
+        let arguments: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => {
+                let [x, y, z] = numbers(&arguments, 3, 4).map_err(error)?;
+                positions.push(Point3::new(x, y, z));
+            }
+            "vt" => {
+                let [u, v, _] = numbers(&arguments, 1, 3).map_err(error)?;
+                uvs.push((u, v));
+            }
+            "vn" => {
+                let [x, y, z] = numbers(&arguments, 3, 3).map_err(error)?;
+                normals.push(Vec3::new(x, y, z));
+            }
+            "f" => {
+                if arguments.len() < 3 {
+                    return Err(error(format!(
+                        "a face needs at least 3 vertices, found {}",
+                        arguments.len()
+                    )));
+                }
+                let counts = (positions.len(), uvs.len(), normals.len());
+                let vertices = arguments
+                    .iter()
+                    .map(|vertex| face_vertex(vertex, counts))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(error)?;
+
+                let faces = groups[current].faces(material.clone());
+                for i in 1..vertices.len() - 1 {
+                    faces.push([vertices[0], vertices[i], vertices[i + 1]]);
+                }
+            }
+            "g" | "o" => {
+                let name = if arguments.is_empty() {
+                    "default".to_string()
+                } else {
+                    arguments.join(" ")
+                };
+                current = match groups.iter().position(|group| group.name == name) {
+                    Some(index) => index,
+                    None => {
+                        groups.push(Group::new(&name));
+                        groups.len() - 1
+                    }
+                };
+            }
+            "usemtl" => {
+                let name = arguments.join(" ");
+                if !materials.contains_key(&name) {
+                    return Err(error(format!("unknown material '{}'", name)));
+                }
+                material = Some(name);
+            }
+            "mtllib" => {
+                for library in &arguments {
+                    let library = path.with_file_name(library);
+                    materials.extend(load_materials(&library)?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let default: Rc<dyn Material> = Rc::new(Lambertian::from_color(Color::new(0.8, 0.8, 0.8)));
+    let mut shapes = Vec::new();
+    for group in groups {
+        let mut list = ShapeList::default();
+        for (material, faces) in group.meshes {
+            let mesh = mesh(&faces, &positions, &uvs, &normals);
+            let material = match material {
+                Some(name) => Rc::clone(&materials[&name]),
+                None => Rc::clone(&default),
+            };
+            list.add(Rc::new(TriangleMesh::new(mesh, material, rng)));
+        }
+        if !list.shapes.is_empty() {
+            shapes.push((group.name, list));
+        }
+    }
+    Ok(shapes)
+}
+
+// Indices of a position, and optionally a uv-coordinate and a normal, starting from zero.
+type FaceVertex = (usize, Option<usize>, Option<usize>);
+
+struct Group {
+    name: String,
+    meshes: Vec<(Option<String>, Vec<[FaceVertex; 3]>)>,
+}
+
+impl Group {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            meshes: Vec::new(),
+        }
+    }
+
+    fn faces(&mut self, material: Option<String>) -> &mut Vec<[FaceVertex; 3]> {
+        let index = match self.meshes.iter().position(|(m, _)| *m == material) {
+            Some(index) => index,
+            None => {
+                self.meshes.push((material, Vec::new()));
+                self.meshes.len() - 1
+            }
+        };
+        &mut self.meshes[index].1
+    }
+}
+
+// Parses between `min` and `max` numbers, filling in the missing ones with zero.
+fn numbers(arguments: &[&str], min: usize, max: usize) -> Result<[f64; 3], String> {
+    if arguments.len() < min || arguments.len() > max {
+        return Err(format!(
+            "expected {} to {} numbers, found {}",
+            min,
+            max,
+            arguments.len()
+        ));
+    }
+
+    let mut values = [0.0; 3];
+    for (value, argument) in values.iter_mut().zip(arguments) {
+        *value = argument
+            .parse()
+            .map_err(|_| format!("invalid number '{}'", argument))?;
+    }
+    Ok(values)
+}
+
+// Parses `v`, `v/vt`, `v//vn` or `v/vt/vn`, where negative indices count back from the last
+// element so far.
+fn face_vertex(vertex: &str, counts: (usize, usize, usize)) -> Result<FaceVertex, String> {
+    let parts: Vec<&str> = vertex.split('/').collect();
+    if parts.len() > 3 || parts[0].is_empty() {
+        return Err(format!("invalid face vertex '{}'", vertex));
+    }
+
+    let index = |part: &str, count: usize| -> Result<Option<usize>, String> {
+        if part.is_empty() {
+            return Ok(None);
+        }
+        let index: i64 = part
+            .parse()
+            .map_err(|_| format!("invalid index '{}' in face vertex '{}'", part, vertex))?;
+        let resolved = if index < 0 {
+            count as i64 + index
+        } else {
+            index - 1
+        };
+        if index == 0 || resolved < 0 || resolved >= count as i64 {
+            return Err(format!(
+                "index {} out of range in face vertex '{}'",
+                index, vertex
+            ));
+        }
+        Ok(Some(resolved as usize))
+    };
+
+    let position = index(parts[0], counts.0)?.unwrap();
+    let uv = match parts.get(1) {
+        Some(part) => index(part, counts.1)?,
+        None => None,
+    };
+    let normal = match parts.get(2) {
+        Some(part) => index(part, counts.2)?,
+        None => None,
+    };
+    Ok((position, uv, normal))
+}
+
+// Builds the vertex buffers for some faces, with one vertex for each distinct combination of
+// position, uv-coordinate and normal. Normals and uv-coordinates are only kept when every vertex
+// has them.
+fn mesh(
+    faces: &[[FaceVertex; 3]],
+    positions: &[Point3],
+    uvs: &[(f64, f64)],
+    normals: &[Vec3],
+) -> Mesh {
+    let mut vertices: HashMap<FaceVertex, usize> = HashMap::new();
+    let mut order = Vec::new();
+    let indices = faces
+        .iter()
+        .map(|face| {
+            face.map(|vertex| {
+                *vertices.entry(vertex).or_insert_with(|| {
+                    order.push(vertex);
+                    order.len() - 1
+                })
+            })
+        })
+        .collect();
+
+    let all_uvs = order.iter().all(|(_, uv, _)| uv.is_some());
+    let all_normals = order.iter().all(|(_, _, normal)| normal.is_some());
+    Mesh {
+        positions: order.iter().map(|&(p, _, _)| positions[p]).collect(),
+        uvs: all_uvs.then(|| order.iter().map(|&(_, uv, _)| uvs[uv.unwrap()]).collect()),
+        normals: all_normals.then(|| order.iter().map(|&(_, _, n)| normals[n.unwrap()]).collect()),
+        colors: None,
+        indices,
+    }
+}
+
+// The MTL properties used to pick one of the crate's materials.
+#[derive(Default)]
+struct MaterialProperties {
+    diffuse: Option<Color>,
+    specular: Option<Color>,
+    emission: Option<Color>,
+    shininess: Option<f64>,
+    index_of_refraction: Option<f64>,
+    opacity: Option<f64>,
+    illumination: Option<u32>,
+    texture: Option<Rc<dyn Texture>>,
+}
+
+impl MaterialProperties {
+    // Emitting materials become lights, transparent ones glass, mirror-like ones metal and the
+    // rest diffuse.
+    fn material(&self) -> Rc<dyn Material> {
+        let diffuse: Rc<dyn Texture> = match &self.texture {
+            Some(texture) => Rc::clone(texture),
+            None => Rc::new(SolidColor::new(
+                self.diffuse.unwrap_or(Color::new(0.8, 0.8, 0.8)),
+            )),
+        };
+        let index_of_refraction = self.index_of_refraction.unwrap_or(1.5);
+
+        if let Some(emission) = self.emission.filter(|e| e.luminance() > 0.0) {
+            return Rc::new(DiffuseLight::from_color(emission));
+        }
+        if self.opacity.is_some_and(|d| d < 1.0) || matches!(self.illumination, Some(4 | 6 | 7 | 9))
+        {
+            return Rc::new(Dielectric::new(index_of_refraction));
+        }
+        if self.illumination == Some(3) || self.illumination == Some(5) {
+            let specular = self.specular.unwrap_or(Color::new(1.0, 1.0, 1.0));
+            // The roughness with about the same highlight as a Phong exponent.
+            let fuzz = f64::sqrt(2.0 / (self.shininess.unwrap_or(1000.0) + 2.0));
+            return Rc::new(Metal::from_color(specular, fuzz));
+        }
+        Rc::new(Lambertian::new(diffuse))
+    }
+}
+
+fn load_materials(path: &Path) -> Result<HashMap<String, Rc<dyn Material>>, LoadError> {
+    let source = read_to_string(path)?;
+    let mut properties: Vec<(String, MaterialProperties)> = Vec::new();
+
+    for (number, line) in source.lines().enumerate() {
+        let number = number + 1;
+        let error = |message: String| LoadError::parse(path, Some(number), message);
+
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) if !keyword.starts_with('#') => keyword,
+            _ => continue,
+        };
+        let arguments: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            properties.push((arguments.join(" "), MaterialProperties::default()));
+            continue;
+        }
+        let current = match properties.last_mut() {
+            Some((_, current)) => current,
+            None if ["Kd", "Ks", "Ke", "Ns", "Ni", "d", "Tr", "illum", "map_Kd"]
+                .contains(&keyword) =>
+            {
+                return Err(error(format!("'{}' before any 'newmtl'", keyword)))
+            }
+            None => continue,
+        };
+
+        // A single number is a gray.
+        let color = || {
+            if arguments.len() == 2 {
+                return Err(error("expected 1 or 3 numbers, found 2".to_string()));
+            }
+            let [r, g, b] = numbers(&arguments, 1, 3).map_err(error)?;
+            Ok(if arguments.len() == 1 {
+                Color::new(r, r, r)
+            } else {
+                Color::new(r, g, b)
+            })
+        };
+        let number = || Ok(numbers(&arguments, 1, 1).map_err(error)?[0]);
+
+        match keyword {
+            "Kd" => current.diffuse = Some(color()?),
+            "Ks" => current.specular = Some(color()?),
+            "Ke" => current.emission = Some(color()?),
+            "Ns" => current.shininess = Some(number()?),
+            "Ni" => current.index_of_refraction = Some(number()?),
+            "d" => current.opacity = Some(number()?),
+            "Tr" => current.opacity = Some(1.0 - number()?),
+            "illum" => current.illumination = Some(number()? as u32),
+            "map_Kd" => {
+                // Options like `-s 1 1 1` come before the file name.
+                let filename = arguments
+                    .last()
+                    .ok_or_else(|| error("'map_Kd' needs a file name".to_string()))?;
+                let texture = path.with_file_name(filename);
+                let image = ::image::open(&texture).map_err(|e| {
+                    error(format!("can't load texture '{}': {}", texture.display(), e))
+                })?;
+                current.texture = Some(Rc::new(Image::from_image(&image)));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(properties
+        .into_iter()
+        .map(|(name, properties)| (name, properties.material()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::fixture;
+    use crate::ray::Ray;
+    use crate::shape::Shape;
+
+    fn load_error(name: &str, contents: &[u8]) -> (Option<usize>, String) {
+        match load_groups(&fixture(name, contents), &mut rand::thread_rng()) {
+            Err(LoadError::Parse { line, message, .. }) => (line, message),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    fn material_error(name: &str, contents: &[u8]) -> (Option<usize>, String) {
+        match load_materials(Path::new(&fixture(name, contents))) {
+            Err(LoadError::Parse { line, message, .. }) => (line, message),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    fn hits(shape: &dyn Shape, x: f64, y: f64) -> bool {
+        let ray = Ray::new(Point3::new(x, y, 1.0), Vec3::new(0.0, 0.0, -1.0), 0.0);
+        shape
+            .hit(&ray, 0.001, f64::INFINITY, &mut rand::thread_rng())
+            .is_some()
+    }
+
+    #[test]
+    fn triangulates_polygons_with_negative_indices() {
+        let filename = fixture(
+            "polygon.obj",
+            b"v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\no square\nf -4 -3 -2 -1\n",
+        );
+        let groups = load_groups(&filename, &mut rand::thread_rng()).unwrap();
+        assert_eq!(groups.len(), 1);
+        let (name, square) = &groups[0];
+        assert_eq!(name, "square");
+        assert!(hits(square, 0.8, 0.2));
+        assert!(hits(square, 0.2, 0.8));
+        assert!(!hits(square, 1.2, 0.5));
+    }
+
+    #[test]
+    fn resolves_face_vertices() {
+        assert_eq!(face_vertex("2", (3, 0, 0)), Ok((1, None, None)));
+        assert_eq!(face_vertex("-1//-2", (3, 0, 2)), Ok((2, None, Some(0))));
+        assert_eq!(face_vertex("1/-1/3", (3, 2, 3)), Ok((0, Some(1), Some(2))));
+    }
+
+    #[test]
+    fn rejects_indices_out_of_range() {
+        assert!(face_vertex("0", (3, 0, 0)).is_err());
+        assert!(face_vertex("-4", (3, 0, 0)).is_err());
+        assert_eq!(
+            load_error("range.obj", b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 4\n"),
+            (
+                Some(4),
+                "index 4 out of range in face vertex '4'".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn reports_material_errors_at_their_line() {
+        assert_eq!(
+            material_error("colors.mtl", b"newmtl gray\nKd 0.5\n\nnewmtl bad\nKd 1 0\n"),
+            (Some(5), "expected 1 or 3 numbers, found 2".to_string())
+        );
+        assert_eq!(
+            material_error("order.mtl", b"# no material yet\nKs 1 1 1\n"),
+            (Some(2), "'Ks' before any 'newmtl'".to_string())
+        );
+
+        let (line, message) = material_error(
+            "texture.mtl",
+            b"newmtl textured\nmap_Kd -s 1 1 1 missing.png\n",
+        );
+        assert_eq!(line, Some(2));
+        assert!(message.starts_with("can't load texture"), "{}", message);
+    }
+
+    #[test]
+    fn loads_textures_next_to_the_library() {
+        let filename = fixture("textured.mtl", b"newmtl textured\nmap_Kd pixel.png\n");
+        let texture = Path::new(&filename).with_file_name("pixel.png");
+        ::image::RgbImage::new(1, 1).save(&texture).unwrap();
+        assert!(load_materials(Path::new(&filename)).is_ok());
+    }
+
+    #[test]
+    fn reports_unknown_materials() {
+        fixture("known.mtl", b"newmtl known\nKd 0.5\n");
+        assert_eq!(
+            load_error(
+                "unknown.obj",
+                b"mtllib known.mtl\nusemtl known\nusemtl other\n"
+            ),
+            (Some(3), "unknown material 'other'".to_string())
+        );
+    }
+}
@@ -0,0 +1,492 @@
+use rand::rngs::ThreadRng;
+use std::convert::TryInto;
+use std::path::Path;
+use std::rc::Rc;
+
+use super::{read, srgb_to_linear, LoadError};
+use crate::material::{lambertian::Lambertian, Material};
+use crate::shape::triangle_mesh::{Mesh, TriangleMesh};
+use crate::texture::vertex_colors::VertexColors;
+use crate::vec3::{Color, Point3, Vec3};
+
+/// Loads a Stanford PLY file, in ASCII or binary of either byte order, into a diffuse triangle
+/// mesh, colored by its vertex colors when it has them.
+pub fn load(filename: &str, rng: &mut ThreadRng) -> Result<TriangleMesh, LoadError> {
+    let mesh = load_mesh(filename)?;
+    let gray = Color::new(0.8, 0.8, 0.8);
+    let material: Rc<dyn Material> = match mesh.colors {
+        Some(_) => Rc::new(Lambertian::new(Rc::new(VertexColors::new(gray)))),
+        None => Rc::new(Lambertian::from_color(gray)),
+    };
+    Ok(TriangleMesh::new(mesh, material, rng))
+}
+
+/// Loads the vertex positions, normals, uv-coordinates, colors and faces of a Stanford PLY file,
+/// to be given any material. The colors are used through the `VertexColors` texture.
+pub fn load_mesh(filename: &str) -> Result<Mesh, LoadError> {
+    let path = Path::new(filename);
+    let data = read(path)?;
+    let (header, body) = split_header(path, &data)?;
+    let (format, elements) = parse_header(path, header)?;
+
+    let mut reader = Reader {
+        path,
+        data: body,
+        position: 0,
+        format,
+        // The header ends before the line with 'end_header'.
+        line: header.lines().count() + 2,
+    };
+
+    let mut mesh = Mesh::default();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut colors = Vec::new();
+    let mut has_vertices = false;
+
+    for element in &elements {
+        let find = |names: &[&str]| {
+            element
+                .properties
+                .iter()
+                .position(|property| property.list.is_none() && names.contains(&&*property.name))
+        };
+        let position = [find(&["x"]), find(&["y"]), find(&["z"])];
+        let normal = [find(&["nx"]), find(&["ny"]), find(&["nz"])];
+        let uv = [
+            find(&["u", "s", "texture_u", "texture_s"]),
+            find(&["v", "t", "texture_v", "texture_t"]),
+        ];
+        let color = [
+            find(&["red", "diffuse_red"]),
+            find(&["green", "diffuse_green"]),
+            find(&["blue", "diffuse_blue"]),
+        ];
+        let face = element.properties.iter().position(|property| {
+            property.list.is_some()
+                && (property.name == "vertex_indices" || property.name == "vertex_index")
+        });
+
+        if element.name == "vertex" {
+            if position.iter().any(Option::is_none) {
+                return Err(LoadError::parse(
+                    path,
+                    None,
+                    "the vertices need x, y and z properties",
+                ));
+            }
+            has_vertices = true;
+        }
+
+        for _ in 0..element.count {
+            let mut values = Vec::with_capacity(element.properties.len());
+            let mut list = Vec::new();
+            for (index, property) in element.properties.iter().enumerate() {
+                match property.list {
+                    None => values.push(reader.read(property.kind)?),
+                    Some(count) => {
+                        let count = reader.read(count)?;
+                        if count < 0.0 || count.fract() != 0.0 {
+                            return Err(reader.error(format!("invalid list length {}", count)));
+                        }
+                        let items = (0..count as usize)
+                            .map(|_| reader.read(property.kind))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        if Some(index) == face {
+                            list = items;
+                        }
+                        values.push(0.0);
+                    }
+                }
+            }
+
+            match &*element.name {
+                "vertex" => {
+                    let [x, y, z] = position.map(|i| values[i.unwrap()]);
+                    mesh.positions.push(Point3::new(x, y, z));
+                    if let [Some(x), Some(y), Some(z)] = normal {
+                        normals.push(Vec3::new(values[x], values[y], values[z]));
+                    }
+                    if let [Some(u), Some(v)] = uv {
+                        uvs.push((values[u], values[v]));
+                    }
+                    if let [Some(r), Some(g), Some(b)] = color {
+                        let [r, g, b] =
+                            [r, g, b].map(|i| element.properties[i].kind.color(values[i]));
+                        colors.push(Color::new(r, g, b));
+                    }
+                }
+                "face" if face.is_some() => {
+                    if list.len() < 3 {
+                        return Err(reader.error(format!(
+                            "a face needs at least 3 vertices, found {}",
+                            list.len()
+                        )));
+                    }
+                    let vertices = mesh.positions.len();
+                    let indices = list
+                        .iter()
+                        .map(|&index| {
+                            if index < 0.0 || index.fract() != 0.0 || index >= vertices as f64 {
+                                Err(reader.error(format!("vertex index {} out of range", index)))
+                            } else {
+                                Ok(index as usize)
+                            }
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    for i in 1..indices.len() - 1 {
+                        mesh.indices.push([indices[0], indices[i], indices[i + 1]]);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if !has_vertices || mesh.indices.is_empty() {
+        return Err(LoadError::parse(path, None, "the file has no faces"));
+    }
+
+    let vertices = mesh.positions.len();
+    mesh.normals = Some(normals).filter(|normals| normals.len() == vertices);
+    mesh.uvs = Some(uvs).filter(|uvs| uvs.len() == vertices);
+    mesh.colors = Some(colors).filter(|colors| colors.len() == vertices);
+    Ok(mesh)
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Ascii,
+    LittleEndian,
+    BigEndian,
+}
+
+#[derive(Clone, Copy)]
+enum Scalar {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "char" | "int8" => Some(Self::Int8),
+            "uchar" | "uint8" => Some(Self::UInt8),
+            "short" | "int16" => Some(Self::Int16),
+            "ushort" | "uint16" => Some(Self::UInt16),
+            "int" | "int32" => Some(Self::Int32),
+            "uint" | "uint32" => Some(Self::UInt32),
+            "float" | "float32" => Some(Self::Float32),
+            "double" | "float64" => Some(Self::Float64),
+            _ => None,
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Self::Int8 | Self::UInt8 => 1,
+            Self::Int16 | Self::UInt16 => 2,
+            Self::Int32 | Self::UInt32 | Self::Float32 => 4,
+            Self::Float64 => 8,
+        }
+    }
+
+    // Integer colors are sRGB-encoded over the whole range of their type, floating point ones
+    // are linear from 0 to 1.
+    fn color(self, value: f64) -> f64 {
+        let max = match self {
+            Self::Int8 | Self::UInt8 => u8::MAX as f64,
+            Self::Int16 | Self::UInt16 => u16::MAX as f64,
+            Self::Int32 | Self::UInt32 => u32::MAX as f64,
+            Self::Float32 | Self::Float64 => return value,
+        };
+        srgb_to_linear(value / max)
+    }
+}
+
+struct Property {
+    name: String,
+    kind: Scalar,
+    // The type of the length, for list properties.
+    list: Option<Scalar>,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+fn split_header<'a>(path: &Path, data: &'a [u8]) -> Result<(&'a str, &'a [u8]), LoadError> {
+    const END: &[u8] = b"end_header";
+    let end = data
+        .windows(END.len())
+        .position(|window| window == END)
+        .ok_or_else(|| LoadError::parse(path, None, "missing 'end_header'"))?;
+    let body = match data[end..].iter().position(|&byte| byte == b'\n') {
+        Some(newline) => end + newline + 1,
+        None => data.len(),
+    };
+    let header = std::str::from_utf8(&data[..end])
+        .map_err(|_| LoadError::parse(path, None, "the header is not valid text"))?;
+    Ok((header, &data[body..]))
+}
+
+fn parse_header(path: &Path, header: &str) -> Result<(Format, Vec<Element>), LoadError> {
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+
+    for (number, line) in header.lines().enumerate() {
+        let number = number + 1;
+        let error = |message: String| LoadError::parse(path, Some(number), message);
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        if number == 1 {
+            if tokens != ["ply"] {
+                return Err(error("not a PLY file".to_string()));
+            }
+            continue;
+        }
+
+        let mut add_property = |property| {
+            elements
+                .last_mut()
+                .map(|element| element.properties.push(property))
+                .ok_or_else(|| error("a property before any element".to_string()))
+        };
+        let scalar = |name: &str| {
+            Scalar::parse(name).ok_or_else(|| error(format!("unknown property type '{}'", name)))
+        };
+        match tokens.as_slice() {
+            [] | ["comment", ..] | ["obj_info", ..] => {}
+            ["format", name, _] => {
+                format = Some(match *name {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::LittleEndian,
+                    "binary_big_endian" => Format::BigEndian,
+                    _ => return Err(error(format!("unknown format '{}'", name))),
+                });
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| error(format!("invalid element count '{}'", count)))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, kind, name] => {
+                let property = Property {
+                    name: name.to_string(),
+                    kind: scalar(kind)?,
+                    list: Some(scalar(count)?),
+                };
+                add_property(property)?;
+            }
+            ["property", kind, name] => {
+                let property = Property {
+                    name: name.to_string(),
+                    kind: scalar(kind)?,
+                    list: None,
+                };
+                add_property(property)?;
+            }
+            _ => return Err(error(format!("invalid header line '{}'", line))),
+        }
+    }
+
+    let format = format.ok_or_else(|| LoadError::parse(path, None, "missing 'format'"))?;
+    Ok((format, elements))
+}
+
+// Reads the values of the body one by one, keeping track of the line in ASCII files.
+struct Reader<'a> {
+    path: &'a Path,
+    data: &'a [u8],
+    position: usize,
+    format: Format,
+    line: usize,
+}
+
+impl Reader<'_> {
+    fn read(&mut self, kind: Scalar) -> Result<f64, LoadError> {
+        let little_endian = match self.format {
+            Format::Ascii => return self.read_ascii(),
+            Format::LittleEndian => true,
+            Format::BigEndian => false,
+        };
+
+        let end = self.position + kind.size();
+        let bytes = self
+            .data
+            .get(self.position..end)
+            .ok_or_else(|| self.error("unexpected end of file".to_string()))?;
+        self.position = end;
+
+        macro_rules! number {
+            ($type:ty) => {{
+                let bytes = bytes.try_into().unwrap();
+                if little_endian {
+                    <$type>::from_le_bytes(bytes) as f64
+                } else {
+                    <$type>::from_be_bytes(bytes) as f64
+                }
+            }};
+        }
+        Ok(match kind {
+            Scalar::Int8 => number!(i8),
+            Scalar::UInt8 => number!(u8),
+            Scalar::Int16 => number!(i16),
+            Scalar::UInt16 => number!(u16),
+            Scalar::Int32 => number!(i32),
+            Scalar::UInt32 => number!(u32),
+            Scalar::Float32 => number!(f32),
+            Scalar::Float64 => number!(f64),
+        })
+    }
+
+    fn read_ascii(&mut self) -> Result<f64, LoadError> {
+        while let Some(&byte) = self.data.get(self.position) {
+            if !byte.is_ascii_whitespace() {
+                break;
+            }
+            if byte == b'\n' {
+                self.line += 1;
+            }
+            self.position += 1;
+        }
+
+        let start = self.position;
+        while self
+            .data
+            .get(self.position)
+            .is_some_and(|byte| !byte.is_ascii_whitespace())
+        {
+            self.position += 1;
+        }
+        if start == self.position {
+            return Err(self.error("unexpected end of file".to_string()));
+        }
+
+        let token = String::from_utf8_lossy(&self.data[start..self.position]);
+        token
+            .parse()
+            .map_err(|_| self.error(format!("invalid number '{}'", token)))
+    }
+
+    fn error(&self, message: String) -> LoadError {
+        let line = match self.format {
+            Format::Ascii => Some(self.line),
+            Format::LittleEndian | Format::BigEndian => None,
+        };
+        LoadError::parse(self.path, line, message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::fixture;
+
+    fn coordinates(point: Point3) -> [f64; 3] {
+        [point.x(), point.y(), point.z()]
+    }
+
+    fn error(name: &str, contents: &[u8]) -> (Option<usize>, String) {
+        match load_mesh(&fixture(name, contents)) {
+            Err(LoadError::Parse { line, message, .. }) => (line, message),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    struct Encoding {
+        short: fn(i16) -> [u8; 2],
+        int: fn(i32) -> [u8; 4],
+        float: fn(f32) -> [u8; 4],
+    }
+
+    // A square of four vertices with float positions and a short intensity, made of one quad.
+    fn binary(format: &str, encoding: Encoding) -> Vec<u8> {
+        let mut data = format!(
+            "ply\nformat {} 1.0\nelement vertex 4\nproperty float x\nproperty float y\n\
+             property float z\nproperty short intensity\nelement face 1\n\
+             property list uchar int vertex_indices\nend_header\n",
+            format
+        )
+        .into_bytes();
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)] {
+            data.extend((encoding.float)(x));
+            data.extend((encoding.float)(y));
+            data.extend((encoding.float)(2.0));
+            data.extend((encoding.short)(-1));
+        }
+        data.push(4);
+        for index in 0..4 {
+            data.extend((encoding.int)(index));
+        }
+        data
+    }
+
+    #[test]
+    fn loads_ascii_with_colors() {
+        let filename = fixture(
+            "colors.ply",
+            b"ply\nformat ascii 1.0\ncomment a triangle\nelement vertex 3\n\
+              property float x\nproperty float y\nproperty float z\n\
+              property uchar red\nproperty uchar green\nproperty uchar blue\n\
+              element face 1\nproperty list uchar int vertex_indices\nend_header\n\
+              0 0 0 255 0 0\n1 0 0 0 255 0\n0 1 0 128 128 128\n3 0 1 2\n",
+        );
+        let mesh = load_mesh(&filename).unwrap();
+        assert_eq!(mesh.indices, vec![[0, 1, 2]]);
+        assert_eq!(coordinates(mesh.positions[1]), [1.0, 0.0, 0.0]);
+        assert!(mesh.normals.is_none());
+
+        let colors = mesh.colors.unwrap();
+        assert_eq!(coordinates(colors[0]), [1.0, 0.0, 0.0]);
+        // Integer colors are sRGB-encoded.
+        let gray = colors[2].x();
+        assert!((gray - 0.2158605).abs() < 1e-6, "{}", gray);
+    }
+
+    #[test]
+    fn loads_both_byte_orders() {
+        let little = Encoding {
+            short: i16::to_le_bytes,
+            int: i32::to_le_bytes,
+            float: f32::to_le_bytes,
+        };
+        let big = Encoding {
+            short: i16::to_be_bytes,
+            int: i32::to_be_bytes,
+            float: f32::to_be_bytes,
+        };
+        let little = binary("binary_little_endian", little);
+        let big = binary("binary_big_endian", big);
+        for (name, data) in [("little.ply", little), ("big.ply", big)] {
+            let mesh = load_mesh(&fixture(name, &data)).unwrap();
+            assert_eq!(mesh.indices, vec![[0, 1, 2], [0, 2, 3]]);
+            assert_eq!(coordinates(mesh.positions[2]), [1.0, 1.0, 2.0]);
+            assert!(mesh.colors.is_none());
+        }
+    }
+
+    #[test]
+    fn rejects_indices_out_of_range() {
+        assert_eq!(
+            error(
+                "range.ply",
+                b"ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\n\
+                  property float y\nproperty float z\nelement face 1\n\
+                  property list uchar int vertex_indices\nend_header\n\
+                  0 0 0\n1 0 0\n0 1 0\n3 0 1 3\n",
+            ),
+            (Some(13), "vertex index 3 out of range".to_string())
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use rand::rngs::ThreadRng;
+use std::convert::TryInto;
+use std::path::Path;
+use std::rc::Rc;
+
+use super::{read, LoadError};
+use crate::material::lambertian::Lambertian;
+use crate::shape::triangle_mesh::{Mesh, TriangleMesh};
+use crate::vec3::{Color, Point3};
+
+/// Loads an STL file, in ASCII or binary, into a diffuse triangle mesh.
+pub fn load(filename: &str, rng: &mut ThreadRng) -> Result<TriangleMesh, LoadError> {
+    let mesh = load_mesh(filename)?;
+    let material = Rc::new(Lambertian::from_color(Color::new(0.8, 0.8, 0.8)));
+    Ok(TriangleMesh::new(mesh, material, rng))
+}
+
+/// Loads the triangles of an STL file, to be given any material. The facet normals are left out,
+/// as they always match the winding of the triangles.
+pub fn load_mesh(filename: &str) -> Result<Mesh, LoadError> {
+    let path = Path::new(filename);
+    let data = read(path)?;
+
+    // Binary files can start with "solid" too, but always have the size given by their count.
+    let binary_count = data
+        .get(80..84)
+        .map(|count| u32::from_le_bytes(count.try_into().unwrap()) as usize);
+    let text = &data[data.iter().take_while(|b| b.is_ascii_whitespace()).count()..];
+    let positions = match binary_count {
+        Some(count) if data.len() == 84 + 50 * count => parse_binary(&data[84..]),
+        _ if text.starts_with(b"solid") => parse_ascii(path, &data)?,
+        _ => {
+            return Err(LoadError::parse(
+                path,
+                None,
+                "neither an ASCII STL file nor a binary one of the size given by its count",
+            ))
+        }
+    };
+
+    if positions.is_empty() {
+        return Err(LoadError::parse(path, None, "the file has no triangles"));
+    }
+    Ok(Mesh {
+        indices: (0..positions.len() / 3)
+            .map(|i| [3 * i, 3 * i + 1, 3 * i + 2])
+            .collect(),
+        positions,
+        ..Mesh::default()
+    })
+}
+
+// Each triangle is a normal, three vertices and an attribute byte count.
+fn parse_binary(data: &[u8]) -> Vec<Point3> {
+    let float = |bytes: &[u8]| f32::from_le_bytes(bytes.try_into().unwrap()) as f64;
+    data.chunks_exact(50)
+        .flat_map(|triangle| {
+            (1..4).map(move |vertex| {
+                let bytes = &triangle[12 * vertex..12 * vertex + 12];
+                Point3::new(
+                    float(&bytes[0..4]),
+                    float(&bytes[4..8]),
+                    float(&bytes[8..12]),
+                )
+            })
+        })
+        .collect()
+}
+
+fn parse_ascii(path: &Path, data: &[u8]) -> Result<Vec<Point3>, LoadError> {
+    let source = String::from_utf8_lossy(data);
+    let mut positions = Vec::new();
+    let mut vertices = 0;
+
+    for (number, line) in source.lines().enumerate() {
+        let number = number + 1;
+        let error = |message: String| LoadError::parse(path, Some(number), message);
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        match tokens.as_slice() {
+            ["vertex", coordinates @ ..] => {
+                if coordinates.len() != 3 {
+                    return Err(error(format!(
+                        "expected 3 coordinates, found {}",
+                        coordinates.len()
+                    )));
+                }
+                let mut values = [0.0; 3];
+                for (value, coordinate) in values.iter_mut().zip(coordinates) {
+                    *value = coordinate
+                        .parse()
+                        .map_err(|_| error(format!("invalid number '{}'", coordinate)))?;
+                }
+                positions.push(Point3::new(values[0], values[1], values[2]));
+                vertices += 1;
+            }
+            ["outer", "loop"] => vertices = 0,
+            ["endloop"] if vertices != 3 => {
+                return Err(error(format!(
+                    "a facet needs 3 vertices, found {}",
+                    vertices
+                )))
+            }
+            _ => {}
+        }
+    }
+
+    if positions.len() % 3 != 0 {
+        return Err(LoadError::parse(path, None, "unexpected end of file"));
+    }
+    Ok(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::fixture;
+
+    fn coordinates(point: Point3) -> [f64; 3] {
+        [point.x(), point.y(), point.z()]
+    }
+
+    fn error(name: &str, contents: &[u8]) -> (Option<usize>, String) {
+        match load_mesh(&fixture(name, contents)) {
+            Err(LoadError::Parse { line, message, .. }) => (line, message),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    // One triangle after the given 80 byte header.
+    fn binary(header: &[u8]) -> Vec<u8> {
+        let mut data = header.to_vec();
+        data.resize(80, b' ');
+        data.extend(1u32.to_le_bytes());
+        for value in [
+            0.0f32, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0,
+        ] {
+            data.extend(value.to_le_bytes());
+        }
+        data.extend([0, 0]);
+        data
+    }
+
+    #[test]
+    fn loads_ascii() {
+        let filename = fixture(
+            "ascii.stl",
+            b"  solid ascii\nfacet normal 0 0 1\n outer loop\n  vertex 0 0 0\n  vertex 1 0 0\n\
+              vertex 0 1 0\n endloop\nendfacet\nendsolid ascii\n",
+        );
+        let mesh = load_mesh(&filename).unwrap();
+        assert_eq!(mesh.indices, vec![[0, 1, 2]]);
+        assert_eq!(coordinates(mesh.positions[2]), [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn loads_binary_even_with_a_solid_header() {
+        for (name, header) in [
+            ("binary.stl", &b"exported"[..]),
+            ("solid.stl", &b"solid but binary"[..]),
+        ] {
+            let mesh = load_mesh(&fixture(name, &binary(header))).unwrap();
+            assert_eq!(mesh.indices, vec![[0, 1, 2]]);
+            assert_eq!(coordinates(mesh.positions[1]), [1.0, 0.0, 0.0]);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        assert_eq!(
+            error(
+                "facet.stl",
+                b"solid bad\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nendloop\n",
+            ),
+            (Some(6), "a facet needs 3 vertices, found 2".to_string())
+        );
+
+        let mut truncated = binary(b"exported");
+        truncated.pop();
+        assert_eq!(
+            error("truncated.stl", &truncated),
+            (
+                None,
+                "neither an ASCII STL file nor a binary one of the size given by its count"
+                    .to_string()
+            )
+        );
+    }
+}
@@ -1,40 +1,186 @@
 use rand;
 use rand::rngs::ThreadRng;
+use rand::Rng;
+use std::rc::Rc;
 
+use rtc::atmosphere::Atmosphere;
 use rtc::camera::{Camera, CameraConfig};
+use rtc::light::{self, Light};
 use rtc::ray::Ray;
 use rtc::scenes::{
-    cornell_box, cornell_smoke, earth, last, random, simple_light, two_perlin_spheres, two_spheres,
+    cornell_box, cornell_cloud, cornell_smoke, earth, last, random, simple_light,
+    two_perlin_spheres, two_spheres,
+};
+use rtc::shape::{
+    medium::{Medium, Segment},
+    HitRecord, Shape,
 };
-use rtc::shape::Shape;
 use rtc::vec3::{Color, Point3};
 
+struct Scene<T: Shape> {
+    world: T,
+    background: Option<Color>,
+    atmosphere: Option<Atmosphere>,
+    lights: Vec<Rc<dyn Light>>,
+}
+
+// `lit` tells whether the ray was scattered by a surface or medium whose direct light from the
+// scene's lights was already sampled, so hitting one of those lights must not count it a second
+// time.
 fn ray_color<T: Shape>(
     ray: &Ray,
-    background: Option<Color>,
-    world: &T,
+    scene: &Scene<T>,
     depth: u32,
+    lit: bool,
     rng: &mut ThreadRng,
 ) -> Color {
     if depth <= 0 {
         return Color::default();
     }
 
-    if let Some(rec) = world.hit(ray, 0.001, f64::INFINITY, rng) {
-        let emitted = rec.material().emitted(rec.u(), rec.v(), rec.point());
+    let (in_scattered, sampled) = in_scattering(ray, scene, rng);
+
+    // Fog in front of the closest surface scatters the ray before it gets there.
+    let mut hit = scene.world.hit(ray, 0.001, f64::INFINITY, rng);
+    if let Some(atmosphere) = &scene.atmosphere {
+        let t_max = hit.as_ref().map_or(f64::INFINITY, |rec| rec.t());
+        if let Some(rec) = atmosphere.collide(ray, 0.001, t_max, rng) {
+            hit = Some(rec);
+        }
+    }
+
+    if let Some(rec) = hit {
+        let emitted = if lit
+            && scene
+                .lights
+                .iter()
+                .any(|light| light.is_hit(ray, &rec, rng))
+        {
+            Color::default()
+        } else {
+            rec.material().emitted(&rec)
+        };
+        let direct = if rec.is_medium() {
+            None
+        } else {
+            direct_lighting(ray, &rec, scene, rng)
+        };
         if let Some((scattered, attenuation)) = rec.material().scatter(&ray, &rec, rng) {
-            return attenuation * ray_color(&scattered, background, world, depth - 1, rng);
+            let scattered = scattered.with_wavelength(scattered.wavelength().or(ray.wavelength()));
+            // Surfaces and collisions with the particles of a sampled segment had their light
+            // sampled.
+            let lit = direct.is_some()
+                || sampled.iter().any(|segment| {
+                    segment.t0 <= rec.t()
+                        && rec.t() <= segment.t1
+                        && Rc::ptr_eq(&segment.particles.material(), &rec.material())
+                });
+            return in_scattered
+                + emitted
+                + direct.unwrap_or_default()
+                + attenuation * ray_color(&scattered, scene, depth - 1, lit, rng);
         }
-        return emitted;
+        return in_scattered + emitted + direct.unwrap_or_default();
     }
 
-    if let Some(background) = background {
-        return background;
+    if let Some(background) = scene.background {
+        return in_scattered + background;
     }
 
     let unit = ray.direction().normalized();
     let t = 0.5 * (unit.y() + 1.0);
-    Color::new(1.0, 1.0, 1.0) * (1.0 - t) + Color::new(0.5, 0.7, 1.0) * t
+    in_scattered + Color::new(1.0, 1.0, 1.0) * (1.0 - t) + Color::new(0.5, 0.7, 1.0) * t
+}
+
+// The light from a random one of the scene's lights reflected toward the ray origin by the
+// surface at `rec`, or `None` if the material can't be evaluated for the light's direction.
+fn direct_lighting<T: Shape>(
+    ray: &Ray,
+    rec: &HitRecord,
+    scene: &Scene<T>,
+    rng: &mut ThreadRng,
+) -> Option<Color> {
+    if scene.lights.is_empty() {
+        return None;
+    }
+
+    let light = &scene.lights[rng.gen_range(0..scene.lights.len())];
+    let (point, pdf) = light.sample(rng)?;
+    let reflected = rec.material().evaluate(ray, rec, point - rec.point())?;
+
+    let mut incident = light.intensity(point, rec.point(), ray.time(), &scene.world, rng);
+    if let Some(atmosphere) = &scene.atmosphere {
+        let shadow = Ray::new(rec.point(), point - rec.point(), ray.time());
+        incident *= atmosphere.transmittance(&shadow, 0.0, 1.0, rng);
+    }
+
+    let distance_squared = (point - rec.point()).norm_squared();
+    Some(reflected * incident * scene.lights.len() as f64 / (pdf * distance_squared))
+}
+
+// The light from the scene's lights scattered toward the ray origin by the media along the ray,
+// with one point per medium segment picked by equiangular sampling toward a random light. Also
+// returns the segments whose light was sampled.
+fn in_scattering<'a, T: Shape>(
+    ray: &Ray,
+    scene: &'a Scene<T>,
+    rng: &mut ThreadRng,
+) -> (Color, Vec<Segment<'a>>) {
+    let mut color = Color::default();
+    let mut sampled = Vec::new();
+    if scene.lights.is_empty() {
+        return (color, sampled);
+    }
+
+    let mut segments = Vec::new();
+    scene
+        .world
+        .media(ray, 0.001, f64::INFINITY, rng, &mut segments);
+    if let Some(atmosphere) = &scene.atmosphere {
+        atmosphere.media(ray, 0.001, f64::INFINITY, rng, &mut segments);
+    }
+
+    for segment in segments {
+        let light = &scene.lights[rng.gen_range(0..scene.lights.len())];
+        let (point, pdf) = match light.sample(rng) {
+            Some(sample) => sample,
+            None => continue,
+        };
+        let (t, pdf_t) = light::equiangular(ray, point, segment.t0, segment.t1, rng.gen::<f64>());
+        if !t.is_finite() {
+            continue;
+        }
+        let position = ray.at(t);
+        let rec = HitRecord::in_medium(ray, t, segment.particles.material());
+        let phase = match rec.material().evaluate(ray, &rec, point - position) {
+            Some(phase) => phase,
+            None => continue,
+        };
+        sampled.push(segment);
+
+        // Surfaces and media in front of the scattering point hide it from the ray origin.
+        if scene.world.hit(ray, 0.001, t, rng).is_some() {
+            continue;
+        }
+
+        let mut incident = light.intensity(point, position, ray.time(), &scene.world, rng);
+        if let Some(atmosphere) = &scene.atmosphere {
+            let shadow = Ray::new(position, point - position, ray.time());
+            incident *= atmosphere.transmittance(&shadow, 0.0, 1.0, rng)
+                * atmosphere.transmittance(ray, 0.001, t, rng);
+        }
+        if incident.luminance() <= 0.0 {
+            continue;
+        }
+
+        // The density is per unit distance, while t is measured in ray directions. Both are
+        // taken in the space of the medium, which may be scaled.
+        let density = segment.particles.density(segment.ray.at(t)) * segment.ray.direction().norm();
+        let distance_squared = (point - position).norm_squared();
+        color += density * phase * incident * scene.lights.len() as f64
+            / (pdf * pdf_t * distance_squared);
+    }
+    (color, sampled)
 }
 
 fn main() {
@@ -51,6 +197,8 @@ fn main() {
     let mut at = Point3::new(0.0, 0.0, 0.0);
     let vfov;
     let mut background = None;
+    let mut atmosphere = None;
+    let mut lights: Vec<Rc<dyn Light>> = Vec::new();
     let mut aperture = 0.0;
 
     let case = 0;
@@ -95,7 +243,17 @@ fn main() {
             vfov = 40.0;
         }
         7 => {
-            world = cornell_smoke::build();
+            (world, lights) = cornell_smoke::build();
+            background = Some(Color::new(0.0, 0.0, 0.0));
+            aspect_ratio = 1.0;
+            image_width = 600;
+            samples_per_pixel = 200;
+            from = Point3::new(278.0, 278.0, -800.0);
+            at = Point3::new(278.0, 278.0, 0.0);
+            vfov = 40.0;
+        }
+        8 => {
+            world = cornell_cloud::build(&mut rng);
             background = Some(Color::new(0.0, 0.0, 0.0));
             aspect_ratio = 1.0;
             image_width = 600;
@@ -107,6 +265,7 @@ fn main() {
         _ => {
             world = last::build(&mut rng);
             background = Some(Color::new(0.0, 0.0, 0.0));
+            atmosphere = Some(Atmosphere::new(0.0001, Color::new(1.0, 1.0, 1.0)));
             aspect_ratio = 1.0;
             image_width = 800;
             samples_per_pixel = 200;
@@ -117,6 +276,13 @@ fn main() {
     }
     let image_height: u32 = (image_width as f64 / aspect_ratio) as u32;
 
+    let scene = Scene {
+        world,
+        background,
+        atmosphere,
+        lights,
+    };
+
     // Camera
     let camera = Camera::new(CameraConfig {
         from,
@@ -141,7 +307,7 @@ fn main() {
                 let u = i as f64 / (image_width - 1) as f64;
                 let v = j as f64 / (image_height - 1) as f64;
                 let ray = camera.get_ray(u, v, &mut rng);
-                color += ray_color(&ray, background, &world, MAX_DEPTH, &mut rng);
+                color += ray_color(&ray, &scene, MAX_DEPTH, false, &mut rng);
             }
             println!("{}", Color::format_color(color, samples_per_pixel));
         }
@@ -2,18 +2,37 @@ use rand::rngs::ThreadRng;
 
 use crate::ray::Ray;
 use crate::shape::HitRecord;
-use crate::vec3::{Color, Point3};
+use crate::vec3::{Color, Vec3};
 
+pub mod cloth;
+pub mod coated;
+pub mod conductor;
 pub mod dielectric;
 pub mod diffuse_light;
+pub mod emissive;
 pub mod isotropic;
 pub mod lambertian;
 pub mod metal;
+pub mod mix;
+pub mod normal_mapped;
+pub mod oren_nayar;
+pub mod principled;
+pub mod rough_dielectric;
+pub mod subsurface;
+pub mod thin_film;
+pub mod volume;
 
 pub trait Material {
     fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)>;
 
-    fn emitted(&self, _: f64, _: f64, _: Point3) -> Color {
+    fn emitted(&self, _: &HitRecord) -> Color {
         Color::new(0.0, 0.0, 0.0)
     }
+
+    /// The fraction of light arriving from `direction` that is scattered back along the ray,
+    /// per unit solid angle, including the cosine at surfaces. Participating media and diffuse
+    /// surfaces return it so lights can be sampled directly from them.
+    fn evaluate(&self, _ray: &Ray, _rec: &HitRecord, _direction: Vec3) -> Option<Color> {
+        None
+    }
 }
@@ -0,0 +1,65 @@
+use rand::rngs::ThreadRng;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use super::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::shape::HitRecord;
+use crate::texture::{solid_color::SolidColor, Texture};
+use crate::vec3::{Color, Vec3};
+
+/// Fabric like velvet or satin: a diffuse base with the soft sheen of fibers at grazing angles,
+/// using the "Charlie" sheen distribution of Estevez and Kulla (2017).
+pub struct Cloth {
+    texture: Rc<dyn Texture>,
+    sheen: Rc<dyn Texture>,
+    roughness: Rc<dyn Texture>,
+}
+
+impl Cloth {
+    pub fn new(
+        texture: Rc<dyn Texture>,
+        sheen: Rc<dyn Texture>,
+        roughness: Rc<dyn Texture>,
+    ) -> Self {
+        Self {
+            texture,
+            sheen,
+            roughness,
+        }
+    }
+
+    pub fn from_color(color: Color, sheen: Color, roughness: f64) -> Self {
+        Self::new(
+            Rc::new(SolidColor::new(color)),
+            Rc::new(SolidColor::new(sheen)),
+            Rc::new(SolidColor::from_value(roughness)),
+        )
+    }
+}
+
+impl Material for Cloth {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        let color = self.texture.color_at(rec);
+        let sheen = self.sheen.color_at(rec);
+        let roughness = self.roughness.value_at(rec).clamp(0.0, 1.0);
+        let alpha = f64::max(roughness * roughness, 1e-3);
+
+        let onb = Onb::from_w(rec.normal());
+        let wo = onb.to_local(-ray.direction().normalized());
+        let wi = Vec3::rand_cosine_direction(rng);
+        let wh = (wo + wi).normalized();
+
+        let cos_o = f64::max(wo.z(), 0.0);
+        let cos_i = wi.z();
+        let sin_h = f64::sqrt(f64::max(0.0, 1.0 - wh.z() * wh.z()));
+        let distribution = (2.0 + 1.0 / alpha) * sin_h.powf(1.0 / alpha) / (2.0 * PI);
+        let visibility = 1.0 / (4.0 * (cos_i + cos_o - cos_i * cos_o));
+
+        // With cosine sampling, the weight is π times the BRDF.
+        let attenuation = color + PI * distribution * visibility * sheen;
+        let scattered = Ray::new(rec.point(), onb.to_world(wi), ray.time());
+        Some((scattered, attenuation))
+    }
+}
@@ -0,0 +1,109 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::Material;
+use crate::microfacet::{self, TrowbridgeReitz};
+use crate::onb::Onb;
+use crate::rand;
+use crate::ray::Ray;
+use crate::shape::HitRecord;
+use crate::texture::{solid_color::SolidColor, Texture};
+use crate::vec3::{Color, Vec3};
+
+/// A dielectric coating, like varnish or a clearcoat, over any base material. Light is reflected
+/// by the coating with the Fresnel probability, otherwise it reaches the base material and is
+/// weighted by the Fresnel transmission on the way out.
+pub struct Coated {
+    base: Rc<dyn Material>,
+    index_of_refraction: Rc<dyn Texture>,
+    roughness: Rc<dyn Texture>,
+    tint: Rc<dyn Texture>,
+}
+
+impl Coated {
+    pub fn new(base: Rc<dyn Material>, index_of_refraction: f64, roughness: f64) -> Self {
+        Self::from_textures(
+            base,
+            Rc::new(SolidColor::from_value(index_of_refraction)),
+            Rc::new(SolidColor::from_value(roughness)),
+        )
+    }
+
+    pub fn from_textures(
+        base: Rc<dyn Material>,
+        index_of_refraction: Rc<dyn Texture>,
+        roughness: Rc<dyn Texture>,
+    ) -> Self {
+        Self {
+            base,
+            index_of_refraction,
+            roughness,
+            tint: Rc::new(SolidColor::from_value(1.0)),
+        }
+    }
+
+    /// The color of the coating for light passing straight through it once.
+    pub fn with_tint(mut self, tint: Rc<dyn Texture>) -> Self {
+        self.tint = tint;
+        self
+    }
+}
+
+impl Material for Coated {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        if !rec.front_face() {
+            return self.base.scatter(ray, rec, rng);
+        }
+
+        let index_of_refraction = self.index_of_refraction.value_at(rec);
+        let distribution = TrowbridgeReitz::from_roughness(self.roughness.value_at(rec));
+        let tint = self.tint.color_at(rec);
+
+        let onb = Onb::from_w(rec.normal());
+        let wo = onb.to_local(-ray.direction().normalized());
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let smooth = distribution.is_smooth();
+        let wm = if smooth {
+            Vec3::new(0.0, 0.0, 1.0)
+        } else {
+            distribution.sample_visible(wo, rand(rng), rand(rng))
+        };
+
+        let fresnel = microfacet::fresnel_dielectric(Vec3::dot(wo, wm), index_of_refraction);
+        if fresnel > rand(rng) {
+            let wi = microfacet::reflect(wo, wm);
+            if wi.z() <= 0.0 {
+                return None;
+            }
+            let weight = if smooth {
+                1.0
+            } else {
+                distribution.g(wo, wi) / distribution.g1(wo)
+            };
+            let scattered = Ray::new(rec.point(), onb.to_world(wi), ray.time());
+            return Some((scattered, Color::new(1.0, 1.0, 1.0) * weight));
+        }
+
+        let (scattered, attenuation) = self.base.scatter(ray, rec, rng)?;
+        let cos_i = Vec3::dot(scattered.direction().normalized(), rec.normal());
+        if cos_i <= 0.0 {
+            return Some((scattered, attenuation));
+        }
+
+        let transmission = 1.0 - microfacet::fresnel_dielectric(cos_i, index_of_refraction);
+        let path = 1.0 / wo.z() + 1.0 / cos_i;
+        let absorption = Color::new(
+            tint.x().powf(path),
+            tint.y().powf(path),
+            tint.z().powf(path),
+        );
+        Some((scattered, attenuation * absorption * transmission))
+    }
+
+    fn emitted(&self, rec: &HitRecord) -> Color {
+        self.base.emitted(rec)
+    }
+}
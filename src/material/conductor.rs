@@ -0,0 +1,133 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::Material;
+use crate::microfacet::{self, TrowbridgeReitz};
+use crate::onb::Onb;
+use crate::rand;
+use crate::ray::Ray;
+use crate::shape::HitRecord;
+use crate::texture::{solid_color::SolidColor, Texture};
+use crate::vec3::{Color, Vec3};
+
+pub struct Conductor {
+    eta: Rc<dyn Texture>,
+    k: Rc<dyn Texture>,
+    roughness_x: Rc<dyn Texture>,
+    roughness_y: Rc<dyn Texture>,
+    rotation: Rc<dyn Texture>,
+}
+
+impl Conductor {
+    pub fn new(eta: Color, k: Color, roughness: f64) -> Self {
+        Self::from_textures(
+            Rc::new(SolidColor::new(eta)),
+            Rc::new(SolidColor::new(k)),
+            Rc::new(SolidColor::from_value(roughness)),
+        )
+    }
+
+    pub fn from_textures(
+        eta: Rc<dyn Texture>,
+        k: Rc<dyn Texture>,
+        roughness: Rc<dyn Texture>,
+    ) -> Self {
+        Self {
+            eta,
+            k,
+            roughness_x: Rc::clone(&roughness),
+            roughness_y: roughness,
+            rotation: Rc::new(SolidColor::from_value(0.0)),
+        }
+    }
+
+    pub fn with_roughness(self, roughness: Rc<dyn Texture>) -> Self {
+        self.with_anisotropic_roughness(Rc::clone(&roughness), roughness)
+    }
+
+    /// Uses a separate roughness along the tangent (x) and bitangent (y) of the surface, like
+    /// brushed metal which is rough across the brush strokes and smooth along them.
+    pub fn with_anisotropic_roughness(
+        mut self,
+        roughness_x: Rc<dyn Texture>,
+        roughness_y: Rc<dyn Texture>,
+    ) -> Self {
+        self.roughness_x = roughness_x;
+        self.roughness_y = roughness_y;
+        self
+    }
+
+    /// Rotates the tangent direction counterclockwise around the normal by an angle in degrees.
+    pub fn with_rotation(mut self, rotation: Rc<dyn Texture>) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn gold(roughness: f64) -> Self {
+        Self::new(
+            Color::new(0.143, 0.374, 1.442),
+            Color::new(3.983, 2.385, 1.603),
+            roughness,
+        )
+    }
+
+    pub fn copper(roughness: f64) -> Self {
+        Self::new(
+            Color::new(0.200, 0.924, 1.102),
+            Color::new(3.912, 2.452, 2.142),
+            roughness,
+        )
+    }
+
+    pub fn aluminium(roughness: f64) -> Self {
+        Self::new(
+            Color::new(1.657, 0.880, 0.521),
+            Color::new(9.224, 6.270, 4.837),
+            roughness,
+        )
+    }
+
+    pub fn silver(roughness: f64) -> Self {
+        Self::new(
+            Color::new(0.155, 0.117, 0.138),
+            Color::new(4.828, 3.122, 2.147),
+            roughness,
+        )
+    }
+}
+
+impl Material for Conductor {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        let eta = self.eta.color_at(rec);
+        let k = self.k.color_at(rec);
+        let roughness_x = self.roughness_x.value_at(rec).clamp(0.0, 1.0);
+        let roughness_y = self.roughness_y.value_at(rec).clamp(0.0, 1.0);
+        let distribution = TrowbridgeReitz::new(roughness_x.powi(2), roughness_y.powi(2));
+
+        let rotation = self.rotation.value_at(rec).to_radians();
+        let frame = Onb::from_wu(rec.normal(), rec.dpdu());
+        let tangent = frame.to_world(Vec3::new(rotation.cos(), rotation.sin(), 0.0));
+        let onb = Onb::from_wu(rec.normal(), tangent);
+        let wo = onb.to_local(-ray.direction().normalized());
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let (wi, attenuation) = if distribution.is_smooth() {
+            let wi = Vec3::new(-wo.x(), -wo.y(), wo.z());
+            (wi, microfacet::fresnel_conductor(wo.z(), eta, k))
+        } else {
+            let wm = distribution.sample_visible(wo, rand(rng), rand(rng));
+            let wi = microfacet::reflect(wo, wm);
+            if wi.z() <= 0.0 {
+                return None;
+            }
+            let fresnel = microfacet::fresnel_conductor(Vec3::dot(wo, wm), eta, k);
+            let shadowing = distribution.g(wo, wi) / distribution.g1(wo);
+            (wi, fresnel * shadowing)
+        };
+
+        let scattered = Ray::new(rec.point(), onb.to_world(wi), ray.time());
+        Some((scattered, attenuation))
+    }
+}
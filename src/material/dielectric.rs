@@ -1,38 +1,143 @@
 use rand::rngs::ThreadRng;
+use std::rc::Rc;
 
 use super::Material;
 use crate::rand;
 use crate::ray::Ray;
 use crate::shape::HitRecord;
+use crate::spectrum;
+use crate::texture::Texture;
 use crate::vec3::{Color, Vec3};
 
+#[derive(Clone)]
+pub enum IndexOfRefraction {
+    Constant(f64),
+    Textured(Rc<dyn Texture>),
+    /// n = a + b / λ², with λ in micrometers.
+    Cauchy {
+        a: f64,
+        b: f64,
+    },
+    /// n² = 1 + Σ b λ² / (λ² - c), with λ in micrometers.
+    Sellmeier {
+        b: [f64; 3],
+        c: [f64; 3],
+    },
+}
+
+impl IndexOfRefraction {
+    pub fn at(&self, wavelength: f64, rec: &HitRecord) -> f64 {
+        let lambda2 = (wavelength * 1e-3).powi(2);
+        match self {
+            Self::Constant(index) => *index,
+            Self::Textured(texture) => texture.value_at(rec),
+            Self::Cauchy { a, b } => a + b / lambda2,
+            Self::Sellmeier { b, c } => f64::sqrt(
+                1.0 + (0..3)
+                    .map(|i| b[i] * lambda2 / (lambda2 - c[i]))
+                    .sum::<f64>(),
+            ),
+        }
+    }
+
+    pub fn is_dispersive(&self) -> bool {
+        matches!(self, Self::Cauchy { .. } | Self::Sellmeier { .. })
+    }
+}
+
 pub struct Dielectric {
-    index_of_refraction: f64,
+    index_of_refraction: IndexOfRefraction,
+    absorption: Color,
 }
 
 impl Dielectric {
     pub fn new(index_of_refraction: f64) -> Self {
+        Self::with_index(IndexOfRefraction::Constant(index_of_refraction))
+    }
+
+    pub fn with_index(index_of_refraction: IndexOfRefraction) -> Self {
         Self {
             index_of_refraction,
+            absorption: Color::default(),
         }
     }
+
+    pub fn from_texture(index_of_refraction: Rc<dyn Texture>) -> Self {
+        Self::with_index(IndexOfRefraction::Textured(index_of_refraction))
+    }
+
+    pub fn cauchy(a: f64, b: f64) -> Self {
+        Self::with_index(IndexOfRefraction::Cauchy { a, b })
+    }
+
+    pub fn sellmeier(b: [f64; 3], c: [f64; 3]) -> Self {
+        Self::with_index(IndexOfRefraction::Sellmeier { b, c })
+    }
+
+    /// Schott BK7 crown glass.
+    pub fn bk7() -> Self {
+        Self::sellmeier(
+            [1.039_612_12, 0.231_792_344, 1.010_469_45],
+            [0.006_000_698_67, 0.020_017_914_4, 103.560_653],
+        )
+    }
+
+    /// Light travelling a distance d inside the medium is attenuated by exp(-absorption * d).
+    pub fn with_absorption(mut self, absorption: Color) -> Self {
+        self.absorption = absorption;
+        self
+    }
+
+    /// Sets the absorption such that light travelling `distance` inside the medium keeps `color`.
+    pub fn with_tint(self, color: Color, distance: f64) -> Self {
+        let absorption = Color::new(
+            -color.x().ln() / distance,
+            -color.y().ln() / distance,
+            -color.z().ln() / distance,
+        );
+        self.with_absorption(absorption)
+    }
 }
 
 impl Default for Dielectric {
     fn default() -> Self {
-        Self {
-            index_of_refraction: 0.0,
-        }
+        Self::new(0.0)
     }
 }
 
 impl Material for Dielectric {
     fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
-        let attenuation = Color::new(1.0, 1.0, 1.0);
+        let mut attenuation = Color::new(1.0, 1.0, 1.0);
+        let mut wavelength = ray.wavelength();
+
+        let index_of_refraction = if self.index_of_refraction.is_dispersive() {
+            let lambda = match wavelength {
+                Some(lambda) => lambda,
+                None => {
+                    let (lambda, weight) = spectrum::sample_wavelength(rng);
+                    attenuation *= weight;
+                    wavelength = Some(lambda);
+                    lambda
+                }
+            };
+            self.index_of_refraction.at(lambda, rec)
+        } else {
+            self.index_of_refraction.at(0.0, rec)
+        };
+
+        if !rec.front_face() {
+            let distance = rec.t() * ray.direction().norm();
+            attenuation *= Color::new(
+                f64::exp(-self.absorption.x() * distance),
+                f64::exp(-self.absorption.y() * distance),
+                f64::exp(-self.absorption.z() * distance),
+            );
+        }
+
         let eta = if rec.front_face() {
-            1.0 / self.index_of_refraction
+            1.0 / index_of_refraction
         } else {
-            self.index_of_refraction
+            index_of_refraction
         };
 
         let unit = ray.direction().normalized();
@@ -48,7 +153,7 @@ impl Material for Dielectric {
             unit.refract(rec.normal(), eta)
         };
 
-        let scattered = Ray::new(rec.point(), direction, ray.time());
+        let scattered = Ray::new(rec.point(), direction, ray.time()).with_wavelength(wavelength);
 
         Some((scattered, attenuation))
     }
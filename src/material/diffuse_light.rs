@@ -1,25 +1,67 @@
 use rand::rngs::ThreadRng;
+use std::f64::consts::PI;
 use std::rc::Rc;
 
 use super::Material;
 use crate::ray::Ray;
 use crate::shape::HitRecord;
-use crate::texture::{solid_color::SolidColor, Texture};
-use crate::vec3::{Color, Point3};
+use crate::texture::{blackbody::Blackbody, solid_color::SolidColor, Texture};
+use crate::vec3::Color;
+
+const LUMENS_PER_WATT: f64 = 683.0;
 
 pub struct DiffuseLight {
     texture: Rc<dyn Texture>,
+    intensity: f64,
+    two_sided: bool,
 }
 
 impl DiffuseLight {
     pub fn new(texture: Rc<dyn Texture>) -> Self {
-        Self { texture }
+        Self {
+            texture,
+            intensity: 1.0,
+            two_sided: true,
+        }
     }
 
     pub fn from_color(color: Color) -> Self {
-        Self {
-            texture: Rc::new(SolidColor::new(color)),
-        }
+        Self::new(Rc::new(SolidColor::new(color)))
+    }
+
+    /// A light with the color of a black body at `kelvin`, e.g. 2700K for tungsten or 6500K for
+    /// daylight. The color has unit luminance, so use the intensity or power to set the brightness.
+    pub fn from_temperature(kelvin: f64) -> Self {
+        Self::new(Rc::new(Blackbody::new(kelvin)))
+    }
+
+    /// Only emit light from the front face, i.e. in the direction of the outward normal.
+    pub fn one_sided(mut self) -> Self {
+        self.two_sided = false;
+        self
+    }
+
+    pub fn two_sided(mut self) -> Self {
+        self.two_sided = true;
+        self
+    }
+
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Scales the emission so that a white light on a shape with the given area emits `watts`
+    /// in total. Set the sidedness first, as a two-sided light spreads its power over both faces.
+    pub fn with_watts(mut self, watts: f64, area: f64) -> Self {
+        let sides = if self.two_sided { 2.0 } else { 1.0 };
+        self.intensity = watts / (sides * PI * area);
+        self
+    }
+
+    /// Like `with_watts`, but with the power given as luminous flux.
+    pub fn with_lumens(self, lumens: f64, area: f64) -> Self {
+        self.with_watts(lumens / LUMENS_PER_WATT, area)
     }
 }
 
@@ -28,7 +70,10 @@ impl Material for DiffuseLight {
         None
     }
 
-    fn emitted(&self, u: f64, v: f64, point: Point3) -> Color {
-        self.texture.color(u, v, point)
+    fn emitted(&self, rec: &HitRecord) -> Color {
+        if !self.two_sided && !rec.front_face() {
+            return Color::default();
+        }
+        self.intensity * self.texture.color_at(rec)
     }
 }
@@ -0,0 +1,38 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::Material;
+use crate::ray::Ray;
+use crate::shape::HitRecord;
+use crate::texture::{solid_color::SolidColor, Texture};
+use crate::vec3::{Color, Vec3};
+
+/// Adds emission to a material that scatters light, like a glowing plastic.
+pub struct Emissive {
+    material: Rc<dyn Material>,
+    emission: Rc<dyn Texture>,
+}
+
+impl Emissive {
+    pub fn new(material: Rc<dyn Material>, emission: Rc<dyn Texture>) -> Self {
+        Self { material, emission }
+    }
+
+    pub fn from_color(material: Rc<dyn Material>, emission: Color) -> Self {
+        Self::new(material, Rc::new(SolidColor::new(emission)))
+    }
+}
+
+impl Material for Emissive {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        self.material.scatter(ray, rec, rng)
+    }
+
+    fn emitted(&self, rec: &HitRecord) -> Color {
+        self.material.emitted(rec) + self.emission.color_at(rec)
+    }
+
+    fn evaluate(&self, ray: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        self.material.evaluate(ray, rec, direction)
+    }
+}
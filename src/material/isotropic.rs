@@ -25,7 +25,12 @@ impl Isotropic {
 impl Material for Isotropic {
     fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
         let scattered = Ray::new(rec.point(), Vec3::rand_in_unit_sphere(rng), ray.time());
-        let color = self.texture.color(rec.u(), rec.v(), rec.point());
+        let color = self.texture.color_at(rec);
         Some((scattered, color))
     }
+
+    fn evaluate(&self, _: &Ray, rec: &HitRecord, _: Vec3) -> Option<Color> {
+        let color = self.texture.color_at(rec);
+        Some(color / (4.0 * std::f64::consts::PI))
+    }
 }
@@ -39,7 +39,12 @@ impl Material for Lambertian {
         }
 
         let scattered = Ray::new(rec.point(), scatter_direction, ray.time());
-        let attenuation = self.texture.color(rec.u(), rec.v(), rec.point());
+        let attenuation = self.texture.color_at(rec);
         Some((scattered, attenuation))
     }
+
+    fn evaluate(&self, _: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        let cosine = Vec3::dot(rec.normal(), direction.normalized()).max(0.0);
+        Some(self.texture.color_at(rec) * cosine / std::f64::consts::PI)
+    }
 }
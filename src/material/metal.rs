@@ -1,39 +1,46 @@
 use rand::rngs::ThreadRng;
+use std::rc::Rc;
 
 use super::Material;
 use crate::ray::Ray;
 use crate::shape::HitRecord;
+use crate::texture::{solid_color::SolidColor, Texture};
 use crate::vec3::{Color, Vec3};
 
 pub struct Metal {
-    color: Color,
-    fuzz: f64,
+    texture: Rc<dyn Texture>,
+    fuzz: Rc<dyn Texture>,
 }
 
 impl Metal {
-    pub fn new(color: Color, fuzz: f64) -> Self {
-        Self { color, fuzz }
+    pub fn new(texture: Rc<dyn Texture>, fuzz: Rc<dyn Texture>) -> Self {
+        Self { texture, fuzz }
+    }
+
+    pub fn from_color(color: Color, fuzz: f64) -> Self {
+        Self {
+            texture: Rc::new(SolidColor::new(color)),
+            fuzz: Rc::new(SolidColor::from_value(fuzz)),
+        }
     }
 }
 
 impl Default for Metal {
     fn default() -> Self {
-        Self {
-            color: Color::default(),
-            fuzz: 0.0,
-        }
+        Self::from_color(Color::default(), 0.0)
     }
 }
 
 impl Material for Metal {
     fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        let fuzz = self.fuzz.value_at(rec);
         let reflected = ray.direction().reflect(rec.normal());
         let scattered = Ray::new(
             rec.point(),
-            reflected + self.fuzz * Vec3::rand_in_unit_sphere(rng),
+            reflected + fuzz * Vec3::rand_in_unit_sphere(rng),
             ray.time(),
         );
-        let attenuation = self.color;
+        let attenuation = self.texture.color_at(rec);
         Some((scattered, attenuation))
     }
 }
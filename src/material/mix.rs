@@ -0,0 +1,49 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::Material;
+use crate::rand;
+use crate::ray::Ray;
+use crate::shape::HitRecord;
+use crate::texture::{solid_color::SolidColor, Texture};
+use crate::vec3::Color;
+
+/// Blends two materials, using `second` with a probability given by the mask.
+pub struct Mix {
+    first: Rc<dyn Material>,
+    second: Rc<dyn Material>,
+    mask: Rc<dyn Texture>,
+}
+
+impl Mix {
+    pub fn new(first: Rc<dyn Material>, second: Rc<dyn Material>, mask: Rc<dyn Texture>) -> Self {
+        Self {
+            first,
+            second,
+            mask,
+        }
+    }
+
+    pub fn from_value(first: Rc<dyn Material>, second: Rc<dyn Material>, amount: f64) -> Self {
+        Self::new(first, second, Rc::new(SolidColor::from_value(amount)))
+    }
+
+    fn amount(&self, rec: &HitRecord) -> f64 {
+        self.mask.value_at(rec).clamp(0.0, 1.0)
+    }
+}
+
+impl Material for Mix {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        if self.amount(rec) > rand(rng) {
+            self.second.scatter(ray, rec, rng)
+        } else {
+            self.first.scatter(ray, rec, rng)
+        }
+    }
+
+    fn emitted(&self, rec: &HitRecord) -> Color {
+        let amount = self.amount(rec);
+        (1.0 - amount) * self.first.emitted(rec) + amount * self.second.emitted(rec)
+    }
+}
@@ -0,0 +1,63 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::shape::HitRecord;
+use crate::texture::Texture;
+use crate::vec3::{Color, Vec3};
+
+/// Perturbs the shading normal of a material with a tangent space normal map, where the red and
+/// green channels point along increasing u and v. `scale` strengthens or weakens the bumps.
+pub struct NormalMapped {
+    material: Rc<dyn Material>,
+    normals: Rc<dyn Texture>,
+    scale: f64,
+}
+
+impl NormalMapped {
+    pub fn new(material: Rc<dyn Material>, normals: Rc<dyn Texture>, scale: f64) -> Self {
+        Self {
+            material,
+            normals,
+            scale,
+        }
+    }
+
+    fn mapped(&self, rec: &HitRecord) -> HitRecord {
+        let color = self.normals.color_at(rec);
+        let mut local = Vec3::new(
+            self.scale * (2.0 * color.x() - 1.0),
+            self.scale * (2.0 * color.y() - 1.0),
+            2.0 * color.z() - 1.0,
+        );
+
+        // The map is relative to the outward normal, while the normal of the hit faces the ray.
+        let outward = if rec.front_face() {
+            rec.normal()
+        } else {
+            -rec.normal()
+        };
+        let onb = Onb::from_wu(outward, rec.dpdu());
+        if Vec3::dot(onb.v(), rec.dpdv()) < 0.0 {
+            local[1] = -local[1];
+        }
+        let normal = onb.to_world(local).normalized();
+        rec.with_normal(if rec.front_face() { normal } else { -normal })
+    }
+}
+
+impl Material for NormalMapped {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        self.material.scatter(ray, &self.mapped(rec), rng)
+    }
+
+    fn emitted(&self, rec: &HitRecord) -> Color {
+        self.material.emitted(rec)
+    }
+
+    fn evaluate(&self, ray: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        self.material.evaluate(ray, &self.mapped(rec), direction)
+    }
+}
@@ -0,0 +1,61 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::shape::HitRecord;
+use crate::texture::{solid_color::SolidColor, Texture};
+use crate::vec3::{Color, Vec3};
+
+/// Rough diffuse reflection using the Oren-Nayar model, where `sigma` is the standard deviation
+/// of the microfacet slope angle in degrees. A sigma of zero is Lambertian.
+pub struct OrenNayar {
+    texture: Rc<dyn Texture>,
+    sigma: Rc<dyn Texture>,
+}
+
+impl OrenNayar {
+    pub fn new(texture: Rc<dyn Texture>, sigma: Rc<dyn Texture>) -> Self {
+        Self { texture, sigma }
+    }
+
+    pub fn from_color(color: Color, sigma: f64) -> Self {
+        Self {
+            texture: Rc::new(SolidColor::new(color)),
+            sigma: Rc::new(SolidColor::from_value(sigma)),
+        }
+    }
+}
+
+impl Material for OrenNayar {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        let sigma = self.sigma.value_at(rec).to_radians();
+        let sigma2 = sigma * sigma;
+        let a = 1.0 - sigma2 / (2.0 * (sigma2 + 0.33));
+        let b = 0.45 * sigma2 / (sigma2 + 0.09);
+
+        let onb = Onb::from_w(rec.normal());
+        let wo = onb.to_local(-ray.direction().normalized());
+        let wi = Vec3::rand_cosine_direction(rng);
+
+        let sin_o = f64::sqrt(f64::max(0.0, 1.0 - wo.z() * wo.z()));
+        let sin_i = f64::sqrt(f64::max(0.0, 1.0 - wi.z() * wi.z()));
+
+        let max_cos = if sin_o > 1e-4 && sin_i > 1e-4 {
+            f64::max(0.0, (wi.x() * wo.x() + wi.y() * wo.y()) / (sin_i * sin_o))
+        } else {
+            0.0
+        };
+
+        let (sin_alpha, tan_beta) = if wi.z().abs() > wo.z().abs() {
+            (sin_o, sin_i / wi.z().abs())
+        } else {
+            (sin_i, sin_o / wo.z().abs())
+        };
+
+        let scattered = Ray::new(rec.point(), onb.to_world(wi), ray.time());
+        let attenuation = self.texture.color_at(rec) * (a + b * max_cos * sin_alpha * tan_beta);
+        Some((scattered, attenuation))
+    }
+}
@@ -0,0 +1,198 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::Material;
+use crate::microfacet::{self, TrowbridgeReitz};
+use crate::onb::Onb;
+use crate::rand;
+use crate::ray::Ray;
+use crate::shape::HitRecord;
+use crate::texture::{solid_color::SolidColor, Texture};
+use crate::vec3::{Color, Vec3};
+
+pub struct PrincipledConfig {
+    pub base_color: Rc<dyn Texture>,
+    pub metallic: Rc<dyn Texture>,
+    pub roughness: Rc<dyn Texture>,
+    pub specular: Rc<dyn Texture>,
+    pub specular_tint: Rc<dyn Texture>,
+    pub sheen: Rc<dyn Texture>,
+    pub sheen_tint: Rc<dyn Texture>,
+    pub clearcoat: Rc<dyn Texture>,
+    pub clearcoat_gloss: Rc<dyn Texture>,
+    pub transmission: Rc<dyn Texture>,
+    pub index_of_refraction: Rc<dyn Texture>,
+}
+
+impl Default for PrincipledConfig {
+    fn default() -> Self {
+        Self {
+            base_color: Rc::new(SolidColor::from_value(0.8)),
+            metallic: Rc::new(SolidColor::from_value(0.0)),
+            roughness: Rc::new(SolidColor::from_value(0.5)),
+            specular: Rc::new(SolidColor::from_value(0.5)),
+            specular_tint: Rc::new(SolidColor::from_value(0.0)),
+            sheen: Rc::new(SolidColor::from_value(0.0)),
+            sheen_tint: Rc::new(SolidColor::from_value(0.5)),
+            clearcoat: Rc::new(SolidColor::from_value(0.0)),
+            clearcoat_gloss: Rc::new(SolidColor::from_value(1.0)),
+            transmission: Rc::new(SolidColor::from_value(0.0)),
+            index_of_refraction: Rc::new(SolidColor::from_value(1.5)),
+        }
+    }
+}
+
+/// A Disney-style principled BSDF. Each hit picks one lobe at random (clearcoat, metallic
+/// specular, dielectric specular, transmission or diffuse with sheen) and weights it so the
+/// expected result is the blend of all lobes.
+pub struct Principled {
+    conf: PrincipledConfig,
+}
+
+impl Principled {
+    pub fn new(conf: PrincipledConfig) -> Self {
+        Self { conf }
+    }
+}
+
+impl Material for Principled {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        let base_color = self.conf.base_color.color_at(rec);
+        let metallic = self.conf.metallic.value_at(rec).clamp(0.0, 1.0);
+        let roughness = self.conf.roughness.value_at(rec);
+        let specular = self.conf.specular.value_at(rec);
+        let specular_tint = self.conf.specular_tint.value_at(rec);
+        let sheen = self.conf.sheen.value_at(rec);
+        let sheen_tint = self.conf.sheen_tint.value_at(rec);
+        let clearcoat = self.conf.clearcoat.value_at(rec);
+        let clearcoat_gloss = self.conf.clearcoat_gloss.value_at(rec);
+        let transmission = self.conf.transmission.value_at(rec).clamp(0.0, 1.0);
+        let index_of_refraction = self.conf.index_of_refraction.value_at(rec);
+
+        let onb = Onb::from_w(rec.normal());
+        let wo = onb.to_local(-ray.direction().normalized());
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let distribution = TrowbridgeReitz::from_roughness(roughness);
+        let white = Color::new(1.0, 1.0, 1.0);
+
+        // Rays inside a transmissive object only see the dielectric interface, chosen with the
+        // transmission probability. Opaque back faces are shaded like front faces.
+        if !rec.front_face() && transmission > rand(rng) {
+            let wm = distribution.sample_visible(wo, rand(rng), rand(rng));
+            let eta = 1.0 / index_of_refraction;
+            let (wi, _) = sample_interface(wo, wm, eta, rng)?;
+            let weight = distribution.g(wo, wi) / distribution.g1(wo);
+            return Some(scattered(ray, rec, &onb, wi, white * weight));
+        }
+
+        let clearcoat_probability = 0.25 * clearcoat * schlick(0.04, wo.z());
+        if clearcoat_probability > rand(rng) {
+            let alpha = lerp(0.1, 0.001, clearcoat_gloss);
+            let coat = TrowbridgeReitz::new(alpha, alpha);
+            let wm = coat.sample_visible(wo, rand(rng), rand(rng));
+            let wi = microfacet::reflect(wo, wm);
+            if wi.z() <= 0.0 {
+                return None;
+            }
+            let fresnel = schlick(0.04, Vec3::dot(wo, wm)) / schlick(0.04, wo.z());
+            let weight = fresnel * coat.g(wo, wi) / coat.g1(wo);
+            return Some(scattered(ray, rec, &onb, wi, white * weight));
+        }
+
+        let wm = distribution.sample_visible(wo, rand(rng), rand(rng));
+        let cos_om = Vec3::dot(wo, wm);
+
+        if metallic > rand(rng) {
+            let wi = microfacet::reflect(wo, wm);
+            if wi.z() <= 0.0 {
+                return None;
+            }
+            let fresnel = base_color + (white - base_color) * schlick_weight(cos_om);
+            let weight = distribution.g(wo, wi) / distribution.g1(wo);
+            return Some(scattered(ray, rec, &onb, wi, fresnel * weight));
+        }
+
+        if rec.front_face() && transmission > rand(rng) {
+            let (wi, transmitted) = sample_interface(wo, wm, index_of_refraction, rng)?;
+            let tint = if transmitted { base_color } else { white };
+            let weight = distribution.g(wo, wi) / distribution.g1(wo);
+            return Some(scattered(ray, rec, &onb, wi, tint * weight));
+        }
+
+        let luminance = base_color.luminance();
+        let tint = if luminance > 0.0 {
+            base_color / luminance
+        } else {
+            white
+        };
+
+        let specular_color = 0.08 * specular * lerp_color(white, tint, specular_tint);
+        let fresnel = specular_color + (white - specular_color) * schlick_weight(cos_om);
+        let fresnel_average = (fresnel.x() + fresnel.y() + fresnel.z()) / 3.0;
+
+        if fresnel_average > rand(rng) {
+            let wi = microfacet::reflect(wo, wm);
+            if wi.z() <= 0.0 {
+                return None;
+            }
+            let weight = distribution.g(wo, wi) / distribution.g1(wo) / fresnel_average;
+            return Some(scattered(ray, rec, &onb, wi, fresnel * weight));
+        }
+
+        let wi = Vec3::rand_cosine_direction(rng);
+        let cos_d = Vec3::dot(wi, (wi + wo).normalized());
+        let fd90 = 0.5 + 2.0 * roughness * cos_d * cos_d;
+        let retro_reflection =
+            lerp(1.0, fd90, schlick_weight(wi.z())) * lerp(1.0, fd90, schlick_weight(wo.z()));
+        let sheen_color = sheen * lerp_color(white, tint, sheen_tint);
+
+        let diffuse = base_color * retro_reflection + sheen_color * schlick_weight(cos_d);
+        let layer = (white - fresnel) / (1.0 - fresnel_average);
+        Some(scattered(ray, rec, &onb, wi, diffuse * layer))
+    }
+}
+
+fn scattered(ray: &Ray, rec: &HitRecord, onb: &Onb, wi: Vec3, attenuation: Color) -> (Ray, Color) {
+    (
+        Ray::new(rec.point(), onb.to_world(wi), ray.time()),
+        attenuation,
+    )
+}
+
+// Reflects or refracts `wo` through the microfacet `wm` of a dielectric interface, choosing
+// reflection with the Fresnel probability.
+fn sample_interface(wo: Vec3, wm: Vec3, eta: f64, rng: &mut ThreadRng) -> Option<(Vec3, bool)> {
+    let fresnel = microfacet::fresnel_dielectric(Vec3::dot(wo, wm), eta);
+    if fresnel > rand(rng) {
+        let wi = microfacet::reflect(wo, wm);
+        if wi.z() <= 0.0 {
+            return None;
+        }
+        Some((wi, false))
+    } else {
+        let wi = microfacet::refract(wo, wm, eta)?;
+        if wi.z() >= 0.0 {
+            return None;
+        }
+        Some((wi, true))
+    }
+}
+
+fn schlick_weight(cosine: f64) -> f64 {
+    (1.0 - cosine.clamp(0.0, 1.0)).powi(5)
+}
+
+fn schlick(r0: f64, cosine: f64) -> f64 {
+    r0 + (1.0 - r0) * schlick_weight(cosine)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a * (1.0 - t) + b * t
+}
+
+fn lerp_color(a: Color, b: Color, t: f64) -> Color {
+    a * (1.0 - t) + b * t
+}
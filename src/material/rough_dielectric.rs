@@ -0,0 +1,83 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::Material;
+use crate::microfacet::{self, TrowbridgeReitz};
+use crate::onb::Onb;
+use crate::rand;
+use crate::ray::Ray;
+use crate::shape::HitRecord;
+use crate::texture::{solid_color::SolidColor, Texture};
+use crate::vec3::{Color, Vec3};
+
+pub struct RoughDielectric {
+    index_of_refraction: Rc<dyn Texture>,
+    roughness: Rc<dyn Texture>,
+}
+
+impl RoughDielectric {
+    pub fn new(index_of_refraction: f64, roughness: f64) -> Self {
+        Self::from_textures(
+            Rc::new(SolidColor::from_value(index_of_refraction)),
+            Rc::new(SolidColor::from_value(roughness)),
+        )
+    }
+
+    pub fn from_textures(index_of_refraction: Rc<dyn Texture>, roughness: Rc<dyn Texture>) -> Self {
+        Self {
+            index_of_refraction,
+            roughness,
+        }
+    }
+}
+
+impl Material for RoughDielectric {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        let index_of_refraction = self.index_of_refraction.value_at(rec);
+        let distribution = TrowbridgeReitz::from_roughness(self.roughness.value_at(rec));
+
+        let eta = if rec.front_face() {
+            index_of_refraction
+        } else {
+            1.0 / index_of_refraction
+        };
+
+        let onb = Onb::from_w(rec.normal());
+        let wo = onb.to_local(-ray.direction().normalized());
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let smooth = distribution.is_smooth();
+        let wm = if smooth {
+            Vec3::new(0.0, 0.0, 1.0)
+        } else {
+            distribution.sample_visible(wo, rand(rng), rand(rng))
+        };
+
+        let fresnel = microfacet::fresnel_dielectric(Vec3::dot(wo, wm), eta);
+        let wi = if fresnel > rand(rng) {
+            let wi = microfacet::reflect(wo, wm);
+            if wi.z() <= 0.0 {
+                return None;
+            }
+            wi
+        } else {
+            let wi = microfacet::refract(wo, wm, eta)?;
+            if wi.z() >= 0.0 {
+                return None;
+            }
+            wi
+        };
+
+        let attenuation = if smooth {
+            Color::new(1.0, 1.0, 1.0)
+        } else {
+            let shadowing = distribution.g(wo, wi) / distribution.g1(wo);
+            Color::new(1.0, 1.0, 1.0) * shadowing
+        };
+
+        let scattered = Ray::new(rec.point(), onb.to_world(wi), ray.time());
+        Some((scattered, attenuation))
+    }
+}
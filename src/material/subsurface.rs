@@ -0,0 +1,113 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::Material;
+use crate::microfacet;
+use crate::ray::Ray;
+use crate::shape::HitRecord;
+use crate::texture::{solid_color::SolidColor, Texture};
+use crate::vec3::{Color, Vec3};
+use crate::{rand, rand_int};
+
+/// Random walk subsurface scattering inside a closed shape. Light refracts into the shape and
+/// scatters isotropically inside until it leaves again. Each step inside the shape is one bounce
+/// of the path, so the mean free path should not be tiny compared to the shape.
+pub struct Subsurface {
+    albedo: Rc<dyn Texture>,
+    mean_free_path: Rc<dyn Texture>,
+    index_of_refraction: f64,
+}
+
+impl Subsurface {
+    /// `albedo` is the color of the surface after all scattering, while `mean_free_path` is the
+    /// average distance light travels inside the shape between scattering events, per channel.
+    pub fn new(
+        albedo: Rc<dyn Texture>,
+        mean_free_path: Rc<dyn Texture>,
+        index_of_refraction: f64,
+    ) -> Self {
+        Self {
+            albedo,
+            mean_free_path,
+            index_of_refraction,
+        }
+    }
+
+    pub fn from_color(albedo: Color, mean_free_path: Color, index_of_refraction: f64) -> Self {
+        Self::new(
+            Rc::new(SolidColor::new(albedo)),
+            Rc::new(SolidColor::new(mean_free_path)),
+            index_of_refraction,
+        )
+    }
+}
+
+impl Material for Subsurface {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        if rec.front_face() {
+            let direction = interface(ray, rec, self.index_of_refraction, rng);
+            let scattered = Ray::new(rec.point(), direction, ray.time());
+            return Some((scattered, Color::new(1.0, 1.0, 1.0)));
+        }
+
+        let albedo = single_scattering_albedo(self.albedo.color_at(rec));
+        let mean_free_path = self.mean_free_path.color_at(rec);
+        let sigma_t = Color::new(
+            1.0 / f64::max(mean_free_path.x(), 1e-6),
+            1.0 / f64::max(mean_free_path.y(), 1e-6),
+            1.0 / f64::max(mean_free_path.z(), 1e-6),
+        );
+
+        let ray_length = ray.direction().norm();
+        let distance_inside = rec.t() * ray_length;
+        let distance = -f64::ln(1.0 - rand(rng)) / sigma_t[rand_int(0, 2, rng) as usize];
+
+        if distance < distance_inside {
+            let transmittance = exp(-distance * sigma_t);
+            let pdf = average(sigma_t * transmittance);
+            let scattered = Ray::new(
+                ray.at(distance / ray_length),
+                Vec3::rand_unit(rng),
+                ray.time(),
+            );
+            return Some((scattered, albedo * sigma_t * transmittance / pdf));
+        }
+
+        let transmittance = exp(-distance_inside * sigma_t);
+        let probability = average(transmittance);
+        let direction = interface(ray, rec, 1.0 / self.index_of_refraction, rng);
+        let scattered = Ray::new(rec.point(), direction, ray.time());
+        Some((scattered, transmittance / probability))
+    }
+}
+
+// Reflects or refracts through the smooth boundary of the shape, where `eta` is the ratio of the
+// index of refraction on the far side to the near side.
+fn interface(ray: &Ray, rec: &HitRecord, eta: f64, rng: &mut ThreadRng) -> Vec3 {
+    let wo = -ray.direction().normalized();
+    let fresnel = microfacet::fresnel_dielectric(Vec3::dot(wo, rec.normal()), eta);
+    if fresnel > rand(rng) {
+        return microfacet::reflect(wo, rec.normal());
+    }
+    microfacet::refract(wo, rec.normal(), eta)
+        .unwrap_or_else(|| microfacet::reflect(wo, rec.normal()))
+}
+
+// Inverts the multiple scattering albedo of a random walk to the single scattering albedo,
+// using the fit of Chiang et al. (2016).
+fn single_scattering_albedo(albedo: Color) -> Color {
+    let invert = |a: f64| {
+        let a = a.clamp(0.0, 0.999);
+        let t = 4.097_12 + 4.208_63 * a - f64::sqrt(9.592_17 + 41.680_8 * a + 17.712_6 * a * a);
+        1.0 - t * t
+    };
+    Color::new(invert(albedo.x()), invert(albedo.y()), invert(albedo.z()))
+}
+
+fn exp(v: Vec3) -> Vec3 {
+    Vec3::new(v.x().exp(), v.y().exp(), v.z().exp())
+}
+
+fn average(v: Vec3) -> f64 {
+    (v.x() + v.y() + v.z()) / 3.0
+}
@@ -0,0 +1,237 @@
+use rand::rngs::ThreadRng;
+use std::f64::consts::PI;
+use std::ops::{Add, Div, Mul, Sub};
+use std::rc::Rc;
+
+use super::Material;
+use crate::microfacet;
+use crate::rand;
+use crate::ray::Ray;
+use crate::shape::HitRecord;
+use crate::spectrum;
+use crate::texture::{solid_color::SolidColor, Texture};
+use crate::vec3::{Color, Vec3};
+
+/// The material below the thin film.
+#[derive(Copy, Clone, Debug)]
+pub enum Substrate {
+    Dielectric(f64),
+    /// The complex index of refraction at the red, green and blue wavelengths.
+    Conductor {
+        eta: Color,
+        k: Color,
+    },
+}
+
+/// A smooth thin film coating, like a soap bubble, an oil slick or an anti-reflective coating.
+/// The reflectance is computed per wavelength from the interference of the light reflected at
+/// the top and bottom of the film.
+pub struct ThinFilm {
+    substrate: Substrate,
+    film_index: f64,
+    thickness: Rc<dyn Texture>,
+    min_thickness: f64,
+    max_thickness: f64,
+}
+
+impl ThinFilm {
+    /// `thickness` maps texture values in [0, 1] to a thickness between 0 and 1000 nanometers,
+    /// use `with_thickness_range` to change this.
+    pub fn new(substrate: Substrate, film_index: f64, thickness: Rc<dyn Texture>) -> Self {
+        Self {
+            substrate,
+            film_index,
+            thickness,
+            min_thickness: 0.0,
+            max_thickness: 1000.0,
+        }
+    }
+
+    /// A film with a constant `thickness` in nanometers.
+    pub fn from_thickness(substrate: Substrate, film_index: f64, thickness: f64) -> Self {
+        Self::new(
+            substrate,
+            film_index,
+            Rc::new(SolidColor::from_value(thickness)),
+        )
+        .with_thickness_range(0.0, 1.0)
+    }
+
+    pub fn with_thickness_range(mut self, min_thickness: f64, max_thickness: f64) -> Self {
+        self.min_thickness = min_thickness;
+        self.max_thickness = max_thickness;
+        self
+    }
+}
+
+impl Material for ThinFilm {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        let t = self.thickness.value_at(rec);
+        let thickness = self.min_thickness + t * (self.max_thickness - self.min_thickness);
+
+        let wo = -ray.direction().normalized();
+        let cos_i = Vec3::dot(wo, rec.normal()).clamp(0.0, 1.0);
+
+        // Rays inside a dielectric substrate see the layers in the opposite order.
+        let (outer, inner) = match self.substrate {
+            Substrate::Dielectric(index) if !rec.front_face() => (index, 1.0),
+            Substrate::Dielectric(index) => (1.0, index),
+            Substrate::Conductor { .. } => (1.0, 0.0),
+        };
+
+        let reflectance = spectrum::reflectance_to_rgb(|lambda| {
+            let substrate = match self.substrate {
+                Substrate::Dielectric(_) => Complex::new(inner, 0.0),
+                Substrate::Conductor { eta, k } => {
+                    Complex::new(at_wavelength(eta, lambda), at_wavelength(k, lambda))
+                }
+            };
+            airy_reflectance(cos_i, outer, self.film_index, substrate, thickness, lambda)
+        });
+
+        let reflected = Ray::new(
+            rec.point(),
+            microfacet::reflect(wo, rec.normal()),
+            ray.time(),
+        );
+        if let Substrate::Conductor { .. } = self.substrate {
+            return Some((reflected, reflectance));
+        }
+
+        let probability = (reflectance.x() + reflectance.y() + reflectance.z()) / 3.0;
+        if probability > rand(rng) {
+            return Some((reflected, reflectance / probability));
+        }
+
+        let direction = microfacet::refract(wo, rec.normal(), inner / outer)?;
+        let refracted = Ray::new(rec.point(), direction, ray.time());
+        let white = Color::new(1.0, 1.0, 1.0);
+        Some((refracted, (white - reflectance) / (1.0 - probability)))
+    }
+}
+
+// Linearly interpolates a per channel quantity given at 650, 550 and 450 nanometers.
+fn at_wavelength(color: Color, lambda: f64) -> f64 {
+    if lambda >= 550.0 {
+        let t = ((lambda - 550.0) / 100.0).min(1.0);
+        color.y() + t * (color.x() - color.y())
+    } else {
+        let t = ((550.0 - lambda) / 100.0).min(1.0);
+        color.y() + t * (color.z() - color.y())
+    }
+}
+
+// Reflectance of unpolarized light on a film with index `film` and `thickness` nanometers
+// between a dielectric with index `outer` and a (possibly absorbing) `substrate`.
+fn airy_reflectance(
+    cos_i: f64,
+    outer: f64,
+    film: f64,
+    substrate: Complex,
+    thickness: f64,
+    lambda: f64,
+) -> f64 {
+    let n1 = Complex::new(outer, 0.0);
+    let n2 = Complex::new(film, 0.0);
+    let sin2_i = Complex::new(1.0 - cos_i * cos_i, 0.0);
+
+    let cos1 = Complex::new(cos_i, 0.0);
+    let cos2 = (Complex::new(1.0, 0.0) - sin2_i * (n1 / n2) * (n1 / n2)).sqrt();
+    let cos3 = (Complex::new(1.0, 0.0) - sin2_i * (n1 / substrate) * (n1 / substrate)).sqrt();
+
+    let phase = 4.0 * PI * thickness / lambda;
+    let delta = n2 * cos2 * Complex::new(phase, 0.0);
+    let shift = Complex::new(0.0, 1.0) * delta;
+    let shift = shift.exp();
+
+    let airy = |r12: Complex, r23: Complex| {
+        let numerator = r12 + r23 * shift;
+        let denominator = Complex::new(1.0, 0.0) + r12 * r23 * shift;
+        (numerator / denominator).norm_squared()
+    };
+
+    let s = airy(
+        fresnel_s(n1, cos1, n2, cos2),
+        fresnel_s(n2, cos2, substrate, cos3),
+    );
+    let p = airy(
+        fresnel_p(n1, cos1, n2, cos2),
+        fresnel_p(n2, cos2, substrate, cos3),
+    );
+    (0.5 * (s + p)).clamp(0.0, 1.0)
+}
+
+fn fresnel_s(ni: Complex, cos_i: Complex, nt: Complex, cos_t: Complex) -> Complex {
+    (ni * cos_i - nt * cos_t) / (ni * cos_i + nt * cos_t)
+}
+
+fn fresnel_p(ni: Complex, cos_i: Complex, nt: Complex, cos_t: Complex) -> Complex {
+    (nt * cos_i - ni * cos_t) / (nt * cos_i + ni * cos_t)
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    fn norm_squared(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    fn sqrt(self) -> Self {
+        let norm = self.norm_squared().sqrt();
+        let re = f64::sqrt(0.5 * (norm + self.re));
+        let im = f64::sqrt(0.5 * (norm - self.re).max(0.0));
+        Self::new(re, if self.im < 0.0 { -im } else { im })
+    }
+
+    fn exp(self) -> Self {
+        let scale = self.re.exp();
+        Self::new(scale * self.im.cos(), scale * self.im.sin())
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Self) -> Self::Output {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Self) -> Self::Output {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+
+    fn div(self, other: Self) -> Self::Output {
+        let denominator = other.norm_squared();
+        Complex::new(
+            (self.re * other.re + self.im * other.im) / denominator,
+            (self.im * other.re - self.re * other.im) / denominator,
+        )
+    }
+}
@@ -0,0 +1,61 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::Material;
+use crate::phase::PhaseFunction;
+use crate::ray::Ray;
+use crate::shape::HitRecord;
+use crate::texture::{solid_color::SolidColor, Texture};
+use crate::vec3::{Color, Vec3};
+
+/// Scattering inside a participating medium, with the direction chosen by a phase function.
+/// The medium can also glow, like fire or hot gas. The emission texture gives the radiance of
+/// the medium where it is optically thick, so thinner parts emit proportionally less.
+pub struct Volume {
+    texture: Rc<dyn Texture>,
+    phase: Rc<dyn PhaseFunction>,
+    emission: Option<Rc<dyn Texture>>,
+}
+
+impl Volume {
+    pub fn new(texture: Rc<dyn Texture>, phase: Rc<dyn PhaseFunction>) -> Self {
+        Self {
+            texture,
+            phase,
+            emission: None,
+        }
+    }
+
+    pub fn from_color(color: Color, phase: Rc<dyn PhaseFunction>) -> Self {
+        Self::new(Rc::new(SolidColor::new(color)), phase)
+    }
+
+    pub fn with_emission(mut self, emission: Rc<dyn Texture>) -> Self {
+        self.emission = Some(emission);
+        self
+    }
+}
+
+impl Material for Volume {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        let direction = self.phase.sample(ray.direction().normalized(), rng);
+        let scattered = Ray::new(rec.point(), direction, ray.time());
+        let color = self.texture.color_at(rec);
+        Some((scattered, color))
+    }
+
+    fn evaluate(&self, ray: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        let cos_theta = Vec3::dot(ray.direction().normalized(), direction.normalized());
+        let color = self.texture.color_at(rec);
+        Some(color * self.phase.evaluate(cos_theta))
+    }
+
+    // Collisions happen with a probability proportional to the density, and so does the
+    // emission, so each collision picks up the emitted radiance as is.
+    fn emitted(&self, rec: &HitRecord) -> Color {
+        match &self.emission {
+            Some(emission) => emission.color_at(rec),
+            None => Color::default(),
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::ops::Mul;
+
+use crate::quaternion::Quaternion;
+use crate::vec3::{Point3, Vec3};
+
+/// A 4x4 matrix of an affine transform, applied to column vectors. Products apply the right
+/// matrix first, so `translation * rotation` rotates and then translates.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Matrix {
+    rows: [[f64; 4]; 4],
+}
+
+impl Matrix {
+    pub fn new(rows: [[f64; 4]; 4]) -> Self {
+        Self { rows }
+    }
+
+    /// A matrix from its columns, the layout of e.g. glTF.
+    pub fn from_columns(columns: [[f64; 4]; 4]) -> Self {
+        Self::new(columns).transpose()
+    }
+
+    pub fn identity() -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn translation(offset: Vec3) -> Self {
+        let mut matrix = Self::identity();
+        for i in 0..3 {
+            matrix.rows[i][3] = offset[i];
+        }
+        matrix
+    }
+
+    pub fn scaling(factors: Vec3) -> Self {
+        let mut matrix = Self::identity();
+        for i in 0..3 {
+            matrix.rows[i][i] = factors[i];
+        }
+        matrix
+    }
+
+    /// A rotation by `angle` degrees around `axis`, counterclockwise when looking down the axis.
+    pub fn rotation(axis: Vec3, angle: f64) -> Self {
+        Self::from_quaternion(Quaternion::from_axis_angle(axis, angle))
+    }
+
+    pub fn from_quaternion(rotation: Quaternion) -> Self {
+        let q = rotation.normalized();
+        let (w, x, y, z) = (q.w(), q.x(), q.y(), q.z());
+        Self::new([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+                0.0,
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+                0.0,
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut transposed = *self;
+        for i in 0..4 {
+            for j in 0..4 {
+                transposed.rows[i][j] = self.rows[j][i];
+            }
+        }
+        transposed
+    }
+
+    /// The determinant of the linear part, which is negative for mirroring transforms.
+    pub fn determinant(&self) -> f64 {
+        let [x, y, z] = [0, 1, 2].map(|i| self.column(i));
+        Vec3::dot(x, Vec3::cross(y, z))
+    }
+
+    /// The inverse of an affine transform, or `None` if it squashes space flat.
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant.abs() < 1e-12 {
+            return None;
+        }
+
+        // The rows of the inverse of the linear part are the cross products of its columns.
+        let [x, y, z] = [0, 1, 2].map(|i| self.column(i));
+        let rows = [
+            Vec3::cross(y, z) / determinant,
+            Vec3::cross(z, x) / determinant,
+            Vec3::cross(x, y) / determinant,
+        ];
+        let translation = self.column(3);
+
+        let mut inverse = Self::identity();
+        for (i, row) in rows.iter().enumerate() {
+            inverse.rows[i] = [row.x(), row.y(), row.z(), -Vec3::dot(*row, translation)];
+        }
+        Some(inverse)
+    }
+
+    pub fn transform_point(&self, point: Point3) -> Point3 {
+        self.transform_vector(point) + self.column(3)
+    }
+
+    pub fn transform_vector(&self, vector: Vec3) -> Vec3 {
+        let row = |i: usize| Vec3::new(self.rows[i][0], self.rows[i][1], self.rows[i][2]);
+        Vec3::new(
+            Vec3::dot(row(0), vector),
+            Vec3::dot(row(1), vector),
+            Vec3::dot(row(2), vector),
+        )
+    }
+
+    fn column(&self, index: usize) -> Vec3 {
+        Vec3::new(
+            self.rows[0][index],
+            self.rows[1][index],
+            self.rows[2][index],
+        )
+    }
+}
+
+impl Default for Matrix {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mul for Matrix {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let mut product = Self::new([[0.0; 4]; 4]);
+        for i in 0..4 {
+            for j in 0..4 {
+                product.rows[i][j] = (0..4).map(|k| self.rows[i][k] * other.rows[k][j]).sum();
+            }
+        }
+        product
+    }
+}
@@ -0,0 +1,154 @@
+use std::f64::consts::PI;
+
+use crate::vec3::{Color, Vec3};
+
+/// The GGX (Trowbridge-Reitz) microfacet distribution with Smith masking-shadowing.
+/// All directions are in the local shading frame, with the normal along z.
+#[derive(Copy, Clone, Debug)]
+pub struct TrowbridgeReitz {
+    alpha_x: f64,
+    alpha_y: f64,
+}
+
+impl TrowbridgeReitz {
+    const MIN_ALPHA: f64 = 1e-4;
+
+    pub fn new(alpha_x: f64, alpha_y: f64) -> Self {
+        Self {
+            alpha_x: f64::max(alpha_x, Self::MIN_ALPHA),
+            alpha_y: f64::max(alpha_y, Self::MIN_ALPHA),
+        }
+    }
+
+    /// Maps a perceptual roughness in [0, 1] to the distribution, using alpha = roughness².
+    pub fn from_roughness(roughness: f64) -> Self {
+        let alpha = roughness.clamp(0.0, 1.0).powi(2);
+        Self::new(alpha, alpha)
+    }
+
+    pub fn is_smooth(&self) -> bool {
+        f64::max(self.alpha_x, self.alpha_y) < 1e-3
+    }
+
+    pub fn d(&self, wm: Vec3) -> f64 {
+        if wm.z() <= 0.0 {
+            return 0.0;
+        }
+        let x = wm.x() / self.alpha_x;
+        let y = wm.y() / self.alpha_y;
+        let e = x * x + y * y + wm.z() * wm.z();
+        1.0 / (PI * self.alpha_x * self.alpha_y * e * e)
+    }
+
+    pub fn lambda(&self, w: Vec3) -> f64 {
+        let cos2 = w.z() * w.z();
+        if cos2 == 0.0 {
+            return f64::INFINITY;
+        }
+        let x = self.alpha_x * w.x();
+        let y = self.alpha_y * w.y();
+        let alpha2_tan2 = (x * x + y * y) / cos2;
+        0.5 * (f64::sqrt(1.0 + alpha2_tan2) - 1.0)
+    }
+
+    pub fn g1(&self, w: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    pub fn g(&self, wo: Vec3, wi: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// Samples a microfacet normal from the distribution of normals visible from `wo`
+    /// (Heitz 2018). `wo` must lie in the upper hemisphere.
+    pub fn sample_visible(&self, wo: Vec3, u1: f64, u2: f64) -> Vec3 {
+        let vh = Vec3::new(self.alpha_x * wo.x(), self.alpha_y * wo.y(), wo.z()).normalized();
+
+        let len2 = vh.x() * vh.x() + vh.y() * vh.y();
+        let t1 = if len2 > 0.0 {
+            Vec3::new(-vh.y(), vh.x(), 0.0) / len2.sqrt()
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let t2 = Vec3::cross(vh, t1);
+
+        let r = u1.sqrt();
+        let phi = 2.0 * PI * u2;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z());
+        let p2 = (1.0 - s) * f64::sqrt(1.0 - p1 * p1) + s * r * phi.sin();
+
+        let nh = p1 * t1 + p2 * t2 + f64::sqrt(f64::max(0.0, 1.0 - p1 * p1 - p2 * p2)) * vh;
+        Vec3::new(
+            self.alpha_x * nh.x(),
+            self.alpha_y * nh.y(),
+            f64::max(0.0, nh.z()),
+        )
+        .normalized()
+    }
+}
+
+/// Mirrors `w` around the microfacet normal `wm`.
+pub fn reflect(w: Vec3, wm: Vec3) -> Vec3 {
+    -w + 2.0 * Vec3::dot(w, wm) * wm
+}
+
+/// Fresnel reflectance of a conductor with complex index of refraction `eta + i k`, for
+/// light arriving from a medium with index 1.
+pub fn fresnel_conductor(cos_i: f64, eta: Color, k: Color) -> Color {
+    Color::new(
+        fresnel_conductor_channel(cos_i, eta.x(), k.x()),
+        fresnel_conductor_channel(cos_i, eta.y(), k.y()),
+        fresnel_conductor_channel(cos_i, eta.z(), k.z()),
+    )
+}
+
+fn fresnel_conductor_channel(cos_i: f64, eta: f64, k: f64) -> f64 {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let cos2 = cos_i * cos_i;
+    let sin2 = 1.0 - cos2;
+    let eta2 = eta * eta;
+    let k2 = k * k;
+
+    let t0 = eta2 - k2 - sin2;
+    let a2_plus_b2 = f64::sqrt(t0 * t0 + 4.0 * eta2 * k2);
+    let t1 = a2_plus_b2 + cos2;
+    let a = f64::sqrt(f64::max(0.0, 0.5 * (a2_plus_b2 + t0)));
+    let t2 = 2.0 * cos_i * a;
+    let rs = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2_plus_b2 + sin2 * sin2;
+    let t4 = t2 * sin2;
+    let rp = rs * (t3 - t4) / (t3 + t4);
+
+    0.5 * (rp + rs)
+}
+
+/// Refracts `w` through the microfacet normal `wm`, where `w` lies on the same side as `wm`
+/// and `eta` is the ratio of the index of refraction on the far side to the near side.
+/// Returns `None` on total internal reflection.
+pub fn refract(w: Vec3, wm: Vec3, eta: f64) -> Option<Vec3> {
+    let cos_i = Vec3::dot(w, wm);
+    let sin2_i = f64::max(0.0, 1.0 - cos_i * cos_i);
+    let sin2_t = sin2_i / (eta * eta);
+    if sin2_t >= 1.0 {
+        return None;
+    }
+    let cos_t = f64::sqrt(1.0 - sin2_t);
+    Some(-w / eta + (cos_i / eta - cos_t) * wm)
+}
+
+/// Exact Fresnel reflectance of an interface between two dielectrics, where `eta` is the ratio
+/// of the index of refraction on the transmitted side to the incident side.
+pub fn fresnel_dielectric(cos_i: f64, eta: f64) -> f64 {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return 1.0;
+    }
+    let cos_t = f64::sqrt(1.0 - sin2_t);
+
+    let r_parallel = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let r_perpendicular = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    0.5 * (r_parallel * r_parallel + r_perpendicular * r_perpendicular)
+}
@@ -0,0 +1,59 @@
+use crate::vec3::Vec3;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Onb {
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+}
+
+impl Onb {
+    pub fn from_w(n: Vec3) -> Self {
+        let w = n.normalized();
+        let a = if w.x().abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let v = Vec3::cross(w, a).normalized();
+        let u = Vec3::cross(v, w);
+        Self { u, v, w }
+    }
+
+    /// A basis with `w` along `n` and `u` along the part of `tangent` perpendicular to `n`.
+    /// Falls back to `from_w` if the tangent is zero or parallel to `n`.
+    pub fn from_wu(n: Vec3, tangent: Vec3) -> Self {
+        let w = n.normalized();
+        let v = Vec3::cross(w, tangent);
+        if v.norm_squared() < 1e-12 {
+            return Self::from_w(n);
+        }
+        let v = v.normalized();
+        let u = Vec3::cross(v, w);
+        Self { u, v, w }
+    }
+
+    pub fn u(&self) -> Vec3 {
+        self.u
+    }
+
+    pub fn v(&self) -> Vec3 {
+        self.v
+    }
+
+    pub fn w(&self) -> Vec3 {
+        self.w
+    }
+
+    pub fn to_world(&self, a: Vec3) -> Vec3 {
+        a.x() * self.u + a.y() * self.v + a.z() * self.w
+    }
+
+    pub fn to_local(&self, a: Vec3) -> Vec3 {
+        Vec3::new(
+            Vec3::dot(a, self.u),
+            Vec3::dot(a, self.v),
+            Vec3::dot(a, self.w),
+        )
+    }
+}
@@ -0,0 +1,31 @@
+use rand::rngs::ThreadRng;
+
+use crate::onb::Onb;
+use crate::rand;
+use crate::vec3::Vec3;
+
+pub mod double_henyey_greenstein;
+pub mod henyey_greenstein;
+pub mod isotropic;
+pub mod rayleigh;
+
+/// The angular distribution of light scattered in a participating medium. The angle is measured
+/// between the direction the light was travelling in and the scattered direction, so a positive
+/// cosine means forward scattering.
+pub trait PhaseFunction {
+    fn evaluate(&self, cos_theta: f64) -> f64;
+
+    /// Samples a scattered direction for light travelling along the unit vector `direction`,
+    /// proportional to `evaluate`.
+    fn sample(&self, direction: Vec3, rng: &mut ThreadRng) -> Vec3;
+}
+
+fn direction_around(axis: Vec3, cos_theta: f64, rng: &mut ThreadRng) -> Vec3 {
+    let sin_theta = f64::sqrt(f64::max(0.0, 1.0 - cos_theta * cos_theta));
+    let phi = 2.0 * std::f64::consts::PI * rand(rng);
+    Onb::from_w(axis).to_world(Vec3::new(
+        sin_theta * phi.cos(),
+        sin_theta * phi.sin(),
+        cos_theta,
+    ))
+}
@@ -0,0 +1,38 @@
+use rand::rngs::ThreadRng;
+
+use super::{henyey_greenstein::HenyeyGreenstein, PhaseFunction};
+use crate::rand;
+use crate::vec3::Vec3;
+
+/// A blend of a forward and a backward scattering Henyey-Greenstein lobe, using the first with
+/// the given `weight`.
+pub struct DoubleHenyeyGreenstein {
+    forward: HenyeyGreenstein,
+    backward: HenyeyGreenstein,
+    weight: f64,
+}
+
+impl DoubleHenyeyGreenstein {
+    pub fn new(g_forward: f64, g_backward: f64, weight: f64) -> Self {
+        Self {
+            forward: HenyeyGreenstein::new(g_forward),
+            backward: HenyeyGreenstein::new(g_backward),
+            weight: weight.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl PhaseFunction for DoubleHenyeyGreenstein {
+    fn evaluate(&self, cos_theta: f64) -> f64 {
+        self.weight * self.forward.evaluate(cos_theta)
+            + (1.0 - self.weight) * self.backward.evaluate(cos_theta)
+    }
+
+    fn sample(&self, direction: Vec3, rng: &mut ThreadRng) -> Vec3 {
+        if self.weight > rand(rng) {
+            self.forward.sample(direction, rng)
+        } else {
+            self.backward.sample(direction, rng)
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use rand::rngs::ThreadRng;
+use std::f64::consts::PI;
+
+use super::{direction_around, PhaseFunction};
+use crate::rand;
+use crate::vec3::Vec3;
+
+/// The Henyey-Greenstein phase function, where the asymmetry `g` in (-1, 1) is the average cosine
+/// of the scattering angle: positive values scatter forward, like fog and clouds, and negative
+/// values scatter backward.
+pub struct HenyeyGreenstein {
+    g: f64,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(g: f64) -> Self {
+        Self {
+            g: g.clamp(-0.999, 0.999),
+        }
+    }
+
+    pub fn sample_cos_theta(&self, rng: &mut ThreadRng) -> f64 {
+        let g = self.g;
+        let xi = rand(rng);
+        if g.abs() < 1e-3 {
+            return 1.0 - 2.0 * xi;
+        }
+        let s = (1.0 - g * g) / (1.0 - g + 2.0 * g * xi);
+        ((1.0 + g * g - s * s) / (2.0 * g)).clamp(-1.0, 1.0)
+    }
+}
+
+impl PhaseFunction for HenyeyGreenstein {
+    fn evaluate(&self, cos_theta: f64) -> f64 {
+        let g = self.g;
+        let denominator = 1.0 + g * g - 2.0 * g * cos_theta;
+        (1.0 - g * g) / (4.0 * PI * denominator * denominator.sqrt())
+    }
+
+    fn sample(&self, direction: Vec3, rng: &mut ThreadRng) -> Vec3 {
+        let cos_theta = self.sample_cos_theta(rng);
+        direction_around(direction, cos_theta, rng)
+    }
+}
@@ -0,0 +1,17 @@
+use rand::rngs::ThreadRng;
+use std::f64::consts::PI;
+
+use super::PhaseFunction;
+use crate::vec3::Vec3;
+
+pub struct Isotropic;
+
+impl PhaseFunction for Isotropic {
+    fn evaluate(&self, _: f64) -> f64 {
+        1.0 / (4.0 * PI)
+    }
+
+    fn sample(&self, _: Vec3, rng: &mut ThreadRng) -> Vec3 {
+        Vec3::rand_unit(rng)
+    }
+}
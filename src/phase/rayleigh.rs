@@ -0,0 +1,23 @@
+use rand::rngs::ThreadRng;
+use std::f64::consts::PI;
+
+use super::{direction_around, PhaseFunction};
+use crate::rand;
+use crate::vec3::Vec3;
+
+/// Scattering by particles much smaller than the wavelength, like the molecules in clear air.
+pub struct Rayleigh;
+
+impl PhaseFunction for Rayleigh {
+    fn evaluate(&self, cos_theta: f64) -> f64 {
+        3.0 / (16.0 * PI) * (1.0 + cos_theta * cos_theta)
+    }
+
+    fn sample(&self, direction: Vec3, rng: &mut ThreadRng) -> Vec3 {
+        // Inverts the cdf (cos³ + 3 cos + 4) / 8 with Cardano's formula.
+        let q = 4.0 - 8.0 * rand(rng);
+        let d = f64::sqrt(0.25 * q * q + 1.0);
+        let cos_theta = (f64::cbrt(-0.5 * q + d) + f64::cbrt(-0.5 * q - d)).clamp(-1.0, 1.0);
+        direction_around(direction, cos_theta, rng)
+    }
+}
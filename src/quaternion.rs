@@ -0,0 +1,101 @@
+use std::ops::Mul;
+
+use crate::vec3::Vec3;
+
+/// A rotation as a unit quaternion `w + xi + yj + zk`. Products apply the right rotation first.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Quaternion {
+    w: f64,
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl Quaternion {
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Self { w, x, y, z }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    /// A rotation by `angle` degrees around `axis`, counterclockwise when looking down the axis.
+    pub fn from_axis_angle(axis: Vec3, angle: f64) -> Self {
+        let half = angle.to_radians() / 2.0;
+        let axis = axis.normalized() * half.sin();
+        Self::new(half.cos(), axis.x(), axis.y(), axis.z())
+    }
+
+    pub fn w(&self) -> f64 {
+        self.w
+    }
+
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+
+    pub fn z(&self) -> f64 {
+        self.z
+    }
+
+    pub fn dot(self, other: Self) -> f64 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn normalized(self) -> Self {
+        let norm = self.dot(self).sqrt();
+        Self::new(self.w / norm, self.x / norm, self.y / norm, self.z / norm)
+    }
+
+    /// Spherical linear interpolation from `self` at `t = 0` to `other` at `t = 1`, rotating at a
+    /// constant speed along the shortest way.
+    pub fn slerp(self, other: Self, t: f64) -> Self {
+        let (a, mut b) = (self.normalized(), other.normalized());
+        // `q` and `-q` are the same rotation, and the closer one takes the shorter way.
+        let mut cos = a.dot(b);
+        if cos < 0.0 {
+            b = Self::new(-b.w, -b.x, -b.y, -b.z);
+            cos = -cos;
+        }
+
+        // Nearly equal rotations divide by almost zero, where a normalized lerp is as good.
+        let (wa, wb) = if cos > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let angle = cos.acos();
+            let sin = angle.sin();
+            (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin)
+        };
+        Self::new(
+            wa * a.w + wb * b.w,
+            wa * a.x + wb * b.x,
+            wa * a.y + wb * b.y,
+            wa * a.z + wb * b.z,
+        )
+        .normalized()
+    }
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+        )
+    }
+}
@@ -5,6 +5,7 @@ pub struct Ray {
     origin: Point3,
     direction: Vec3,
     time: f64,
+    wavelength: Option<f64>,
 }
 
 impl Ray {
@@ -13,9 +14,15 @@ impl Ray {
             origin,
             direction,
             time,
+            wavelength: None,
         }
     }
 
+    pub fn with_wavelength(mut self, wavelength: Option<f64>) -> Ray {
+        self.wavelength = wavelength;
+        self
+    }
+
     pub fn origin(&self) -> Point3 {
         self.origin
     }
@@ -28,6 +35,10 @@ impl Ray {
         self.time
     }
 
+    pub fn wavelength(&self) -> Option<f64> {
+        self.wavelength
+    }
+
     pub fn at(&self, t: f64) -> Vec3 {
         self.origin + self.direction * t
     }
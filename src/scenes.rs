@@ -1,4 +1,5 @@
 pub mod cornell_box;
+pub mod cornell_cloud;
 pub mod cornell_smoke;
 pub mod earth;
 pub mod last;
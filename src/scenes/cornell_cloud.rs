@@ -0,0 +1,66 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use crate::density::turbulence::Turbulence;
+use crate::material::{diffuse_light::DiffuseLight, lambertian::Lambertian, Material};
+use crate::phase::henyey_greenstein::HenyeyGreenstein;
+use crate::shape::{
+    flip_face::FlipFace, heterogeneous_medium::HeterogeneousMedium, shape_list::ShapeList,
+    sphere::Sphere, xy_rect::XyRect, xz_rect::XzRect, yz_rect::YzRect,
+};
+use crate::texture::solid_color::SolidColor;
+use crate::vec3::{Color, Point3};
+
+pub fn build(rng: &mut ThreadRng) -> ShapeList {
+    let mut shapes = ShapeList::default();
+
+    let red: Rc<dyn Material> = Rc::new(Lambertian::from_color(Color::new(0.65, 0.05, 0.05)));
+    let white: Rc<dyn Material> = Rc::new(Lambertian::from_color(Color::new(0.73, 0.73, 0.73)));
+    let green: Rc<dyn Material> = Rc::new(Lambertian::from_color(Color::new(0.12, 0.45, 0.15)));
+    let light: Rc<dyn Material> =
+        Rc::new(DiffuseLight::from_color(Color::new(15.0, 15.0, 15.0)).one_sided());
+
+    shapes.add(Rc::new(YzRect::new(0.0, 555.0, 0.0, 555.0, 555.0, green)));
+    shapes.add(Rc::new(YzRect::new(0.0, 555.0, 0.0, 555.0, 0.0, red)));
+    shapes.add(Rc::new(FlipFace::new(Rc::new(XzRect::new(
+        113.0, 443.0, 127.0, 432.0, 554.0, light,
+    )))));
+    shapes.add(Rc::new(XzRect::new(
+        0.0,
+        555.0,
+        0.0,
+        555.0,
+        0.0,
+        Rc::clone(&white),
+    )));
+    shapes.add(Rc::new(XzRect::new(
+        0.0,
+        555.0,
+        0.0,
+        555.0,
+        555.0,
+        Rc::clone(&white),
+    )));
+    shapes.add(Rc::new(XyRect::new(
+        0.0,
+        555.0,
+        0.0,
+        555.0,
+        555.0,
+        Rc::clone(&white),
+    )));
+
+    let boundary = Rc::new(Sphere::new(
+        Point3::new(278.0, 278.0, 278.0),
+        180.0,
+        Rc::clone(&white),
+    ));
+    shapes.add(Rc::new(HeterogeneousMedium::with_phase(
+        boundary,
+        Rc::new(Turbulence::new(0.05, 0.01, 7, rng)),
+        Rc::new(SolidColor::from_value(0.95)),
+        Rc::new(HenyeyGreenstein::new(0.6)),
+    )));
+
+    shapes
+}
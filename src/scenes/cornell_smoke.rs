@@ -1,25 +1,30 @@
 use std::rc::Rc;
 
+use crate::light::{area::AreaLight, Light};
 use crate::material::{diffuse_light::DiffuseLight, lambertian::Lambertian, Material};
 use crate::shape::{
-    constant_medium::ConstantMedium, cube::Cube, rotate_y::RotateY, shape_list::ShapeList,
-    translate::Translate, xy_rect::XyRect, xz_rect::XzRect, yz_rect::YzRect, Shape,
+    constant_medium::ConstantMedium, cube::Cube, flip_face::FlipFace, rotate_y::RotateY,
+    shape_list::ShapeList, translate::Translate, xy_rect::XyRect, xz_rect::XzRect, yz_rect::YzRect,
+    Shape,
 };
 use crate::vec3::{Color, Point3, Vec3};
 
-pub fn build() -> ShapeList {
+pub fn build() -> (ShapeList, Vec<Rc<dyn Light>>) {
     let mut shapes = ShapeList::default();
 
     let red: Rc<dyn Material> = Rc::new(Lambertian::from_color(Color::new(0.65, 0.05, 0.05)));
     let white: Rc<dyn Material> = Rc::new(Lambertian::from_color(Color::new(0.73, 0.73, 0.73)));
     let green: Rc<dyn Material> = Rc::new(Lambertian::from_color(Color::new(0.12, 0.45, 0.15)));
-    let light: Rc<dyn Material> = Rc::new(DiffuseLight::from_color(Color::new(15.0, 15.0, 15.0)));
+    let light: Rc<dyn Material> =
+        Rc::new(DiffuseLight::from_color(Color::new(15.0, 15.0, 15.0)).one_sided());
 
     shapes.add(Rc::new(YzRect::new(0.0, 555.0, 0.0, 555.0, 555.0, green)));
     shapes.add(Rc::new(YzRect::new(0.0, 555.0, 0.0, 555.0, 0.0, red)));
-    shapes.add(Rc::new(XzRect::new(
+    let light: Rc<dyn Shape> = Rc::new(FlipFace::new(Rc::new(XzRect::new(
         113.0, 443.0, 127.0, 432.0, 554.0, light,
-    )));
+    ))));
+    shapes.add(Rc::clone(&light));
+    let lights: Vec<Rc<dyn Light>> = vec![Rc::new(AreaLight::new(light))];
     shapes.add(Rc::new(XzRect::new(
         0.0,
         555.0,
@@ -71,5 +76,5 @@ pub fn build() -> ShapeList {
         Color::new(1.0, 1.0, 1.0),
     )));
 
-    shapes
+    (shapes, lights)
 }
@@ -60,7 +60,7 @@ pub fn build(rng: &mut ThreadRng) -> ShapeList {
     shapes.add(Rc::new(Sphere::new(
         Point3::new(0.0, 150.0, 145.0),
         50.0,
-        Rc::new(Metal::new(Color::new(0.8, 0.8, 0.9), 1.0)),
+        Rc::new(Metal::from_color(Color::new(0.8, 0.8, 0.9), 1.0)),
     )));
 
     let boundary: Rc<dyn Shape> = Rc::new(Sphere::new(
@@ -75,17 +75,6 @@ pub fn build(rng: &mut ThreadRng) -> ShapeList {
         Color::new(0.2, 0.4, 0.9),
     )));
 
-    let boundary: Rc<dyn Shape> = Rc::new(Sphere::new(
-        Point3::new(0.0, 0.0, 0.0),
-        5000.0,
-        Rc::new(Dielectric::new(1.5)),
-    ));
-    shapes.add(Rc::new(ConstantMedium::from_color(
-        boundary,
-        0.0001,
-        Color::new(1.0, 1.0, 1.0),
-    )));
-
     let earth: Rc<dyn Texture> = Rc::new(Image::new("images/earthmap.jpg"));
     shapes.add(Rc::new(Sphere::new(
         Point3::new(400.0, 200.0, 400.0),
@@ -40,7 +40,7 @@ pub fn build(rng: &mut ThreadRng) -> ShapeList {
                 } else if choose_mat < 0.95 {
                     let color = Color::rand_between(0.5, 1.0, rng);
                     let fuzz = 0.5 * rng.gen::<f64>();
-                    let material: Rc<dyn Material> = Rc::new(Metal::new(color, fuzz));
+                    let material: Rc<dyn Material> = Rc::new(Metal::from_color(color, fuzz));
                     world.add(Rc::new(Sphere::new(center, 0.2, material)));
                 } else {
                     let material: Rc<dyn Material> = Rc::new(Dielectric::new(1.5));
@@ -64,7 +64,7 @@ pub fn build(rng: &mut ThreadRng) -> ShapeList {
         material,
     )));
 
-    let material: Rc<dyn Material> = Rc::new(Metal::new(Color::new(0.7, 0.6, 0.5), 0.0));
+    let material: Rc<dyn Material> = Rc::new(Metal::from_color(Color::new(0.7, 0.6, 0.5), 0.0));
     world.add(Rc::new(Sphere::new(
         Point3::new(4.0, 1.0, 0.0),
         1.0,
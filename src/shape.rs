@@ -4,16 +4,24 @@ use std::rc::Rc;
 use crate::aabb::AABB;
 use crate::material::{lambertian::Lambertian, Material};
 use crate::ray::Ray;
-use crate::vec3::{Point3, Vec3};
+use crate::vec3::{Color, Point3, Vec3};
+use medium::Segment;
 
+pub mod animated_transform;
 pub mod bvh_node;
 pub mod constant_medium;
 pub mod cube;
+pub mod flip_face;
+pub mod heterogeneous_medium;
+pub mod medium;
 pub mod moving_sphere;
+pub mod nested_media;
 pub mod rotate_y;
 pub mod shape_list;
 pub mod sphere;
+pub mod transform;
 pub mod translate;
+pub mod triangle_mesh;
 pub mod xy_rect;
 pub mod xz_rect;
 pub mod yz_rect;
@@ -27,6 +35,10 @@ pub struct HitRecord {
     material: Rc<dyn Material>,
     u: f64,
     v: f64,
+    dpdu: Vec3,
+    dpdv: Vec3,
+    color: Option<Color>,
+    medium: bool,
 }
 
 impl HitRecord {
@@ -39,6 +51,27 @@ impl HitRecord {
         }
     }
 
+    /// A collision with a particle of a participating medium, which has no surface to orient.
+    pub fn in_medium(ray: &Ray, t: f64, material: Rc<dyn Material>) -> Self {
+        Self {
+            t,
+            point: ray.at(t),
+            normal: Vec3::new(1.0, 0.0, 0.0),
+            front_face: true,
+            material,
+            medium: true,
+            ..Self::default()
+        }
+    }
+
+    /// The same hit with another shading normal, e.g. from a normal map.
+    pub fn with_normal(&self, normal: Vec3) -> Self {
+        Self {
+            normal,
+            ..self.clone()
+        }
+    }
+
     pub fn point(&self) -> Point3 {
         self.point
     }
@@ -66,6 +99,26 @@ impl HitRecord {
     pub fn v(&self) -> f64 {
         self.v
     }
+
+    /// The tangent of the surface along increasing u.
+    pub fn dpdu(&self) -> Vec3 {
+        self.dpdu
+    }
+
+    /// The bitangent of the surface along increasing v.
+    pub fn dpdv(&self) -> Vec3 {
+        self.dpdv
+    }
+
+    /// The color interpolated from the vertex colors of a mesh, if it has them.
+    pub fn color(&self) -> Option<Color> {
+        self.color
+    }
+
+    /// Whether this is a collision inside a participating medium rather than a surface hit.
+    pub fn is_medium(&self) -> bool {
+        self.medium
+    }
 }
 
 impl Default for HitRecord {
@@ -78,6 +131,10 @@ impl Default for HitRecord {
             material: Rc::new(Lambertian::default()),
             u: 0.0,
             v: 0.0,
+            dpdu: Vec3::default(),
+            dpdv: Vec3::default(),
+            color: None,
+            medium: false,
         }
     }
 }
@@ -85,4 +142,23 @@ impl Default for HitRecord {
 pub trait Shape {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord>;
     fn bounding_box(&self, time0: f64, time1: f64) -> Option<Rc<AABB>>;
+
+    /// Adds the parts of the ray between `t_min` and `t_max` that pass through participating
+    /// media to `segments`, with the particles of each part. Used to sample lights from inside
+    /// media, so shapes wrapping others have to pass it on.
+    fn media<'a>(
+        &'a self,
+        _ray: &Ray,
+        _t_min: f64,
+        _t_max: f64,
+        _rng: &mut ThreadRng,
+        _segments: &mut Vec<Segment<'a>>,
+    ) {
+    }
+
+    /// Picks a point uniformly on the surface, together with the area of the surface. Shapes
+    /// that can't be sampled return `None`, and can't be used as area lights.
+    fn random_point(&self, _rng: &mut ThreadRng) -> Option<(Point3, f64)> {
+        None
+    }
 }
@@ -0,0 +1,156 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::transform::{transform_box, LocalSpace};
+use super::{medium::Segment, HitRecord, Shape};
+use crate::aabb::AABB;
+use crate::matrix::Matrix;
+use crate::quaternion::Quaternion;
+use crate::ray::Ray;
+use crate::vec3::{Point3, Vec3};
+
+/// The placement of a shape at `time`, which is scaled, then rotated and then translated.
+#[derive(Copy, Clone, Debug)]
+pub struct Keyframe {
+    pub time: f64,
+    pub translation: Vec3,
+    pub rotation: Quaternion,
+    pub scale: Vec3,
+}
+
+impl Keyframe {
+    pub fn matrix(&self) -> Matrix {
+        Matrix::translation(self.translation)
+            * Matrix::from_quaternion(self.rotation)
+            * Matrix::scaling(self.scale)
+    }
+
+    /// The placement at `time` between `self` and `next`, with linear translations and scales
+    /// and a rotation at constant speed.
+    fn interpolate(&self, next: &Self, time: f64) -> Self {
+        let t = (time - self.time) / (next.time - self.time);
+        Self {
+            time,
+            translation: self.translation + t * (next.translation - self.translation),
+            rotation: self.rotation.slerp(next.rotation, t),
+            scale: self.scale + t * (next.scale - self.scale),
+        }
+    }
+}
+
+impl Default for Keyframe {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            translation: Vec3::default(),
+            rotation: Quaternion::identity(),
+            scale: Vec3::new(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+/// A shape moved by keyframes at the time of each ray, for motion blur of any shape. The shape
+/// rests at the first keyframe before it and at the last one after it.
+pub struct AnimatedTransform {
+    shape: Rc<dyn Shape>,
+    // Sorted by time.
+    keyframes: Vec<Keyframe>,
+}
+
+impl AnimatedTransform {
+    pub fn new(shape: Rc<dyn Shape>) -> Self {
+        Self {
+            shape,
+            keyframes: Vec::new(),
+        }
+    }
+
+    pub fn with_keyframe(mut self, keyframe: Keyframe) -> Self {
+        let index = self.keyframes.partition_point(|k| k.time <= keyframe.time);
+        self.keyframes.insert(index, keyframe);
+        self
+    }
+
+    pub fn at(&self, time: f64) -> Keyframe {
+        let index = self.keyframes.partition_point(|k| k.time <= time);
+        match (index.checked_sub(1), self.keyframes.get(index)) {
+            (Some(previous), Some(next)) => self.keyframes[previous].interpolate(next, time),
+            (Some(previous), None) => self.keyframes[previous],
+            (None, Some(next)) => *next,
+            (None, None) => Keyframe::default(),
+        }
+    }
+}
+
+impl Shape for AnimatedTransform {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+        // A shape scaled flat at this time can't be hit.
+        LocalSpace::new(self.at(ray.time()).matrix())?.hit(&*self.shape, ray, t_min, t_max, rng)
+    }
+
+    /// The shutter interval is split at the keyframes, and each piece is bounded on its own.
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Rc<AABB>> {
+        let aabb = self.shape.bounding_box(time0, time1)?;
+
+        let mut times = vec![time0];
+        times.extend(
+            self.keyframes
+                .iter()
+                .map(|k| k.time)
+                .filter(|&time| time0 < time && time < time1),
+        );
+        times.push(time1);
+
+        times
+            .windows(2)
+            .map(|pair| bound_piece(&self.at(pair[0]), &self.at(pair[1]), &aabb))
+            .reduce(|a, b| a.surrounding_box(&b))
+            .map(Rc::new)
+    }
+
+    fn media<'a>(
+        &'a self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut ThreadRng,
+        segments: &mut Vec<Segment<'a>>,
+    ) {
+        if let Some(space) = LocalSpace::new(self.at(ray.time()).matrix()) {
+            self.shape
+                .media(&space.ray(ray), t_min, t_max, rng, segments);
+        }
+    }
+}
+
+/// A box around `aabb` for all placements interpolated between `start` and `end`.
+fn bound_piece(start: &Keyframe, end: &Keyframe, aabb: &AABB) -> AABB {
+    // Without rotating, each point moves on a straight line, so the ends bound the piece.
+    if start.rotation.dot(end.rotation).abs() > 1.0 - 1e-12 {
+        return transform_box(&start.matrix(), aabb)
+            .surrounding_box(&transform_box(&end.matrix(), aabb));
+    }
+
+    // Otherwise the rotated points stay within the sphere around the origin reaching the
+    // farthest corner, with each axis scaled by the larger factor.
+    let mut radius: f64 = 0.0;
+    for corner in 0..8 {
+        let mut point = Point3::default();
+        for i in 0..3 {
+            let value = match corner & (1 << i) {
+                0 => aabb.minimum()[i],
+                _ => aabb.maximum()[i],
+            };
+            point[i] = value * start.scale[i].abs().max(end.scale[i].abs());
+        }
+        radius = radius.max(point.norm());
+    }
+
+    let mut minimum = Point3::default();
+    let mut maximum = Point3::default();
+    for i in 0..3 {
+        minimum[i] = start.translation[i].min(end.translation[i]) - radius;
+        maximum[i] = start.translation[i].max(end.translation[i]) + radius;
+    }
+    AABB::new(minimum, maximum)
+}
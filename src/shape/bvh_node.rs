@@ -2,7 +2,7 @@ use rand::rngs::ThreadRng;
 use std::cmp::Ordering::{self, Greater, Less};
 use std::rc::Rc;
 
-use super::{shape_list::ShapeList, HitRecord, Shape};
+use super::{medium::Segment, shape_list::ShapeList, HitRecord, Shape};
 use crate::aabb::AABB;
 use crate::ray::Ray;
 
@@ -121,4 +121,18 @@ impl Shape for BvhNode {
     fn bounding_box(&self, _: f64, _: f64) -> Option<Rc<AABB>> {
         Some(Rc::clone(&self.bounding_box))
     }
+
+    fn media<'a>(
+        &'a self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut ThreadRng,
+        segments: &mut Vec<Segment<'a>>,
+    ) {
+        if self.bounding_box.hit(ray, t_min, t_max) {
+            self.left.media(ray, t_min, t_max, rng, segments);
+            self.right.media(ray, t_min, t_max, rng, segments);
+        }
+    }
 }
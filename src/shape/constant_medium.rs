@@ -1,97 +1,121 @@
 use rand::rngs::ThreadRng;
 use std::rc::Rc;
 
+use super::medium::{self, Medium, Particles, Segment};
 use super::{HitRecord, Shape};
 use crate::aabb::AABB;
-use crate::material::{isotropic::Isotropic, Material};
+use crate::material::{isotropic::Isotropic, volume::Volume, Material};
+use crate::phase::PhaseFunction;
+use crate::rand;
 use crate::ray::Ray;
 use crate::texture::Texture;
-use crate::vec3::{Color, Vec3};
+use crate::vec3::{Color, Point3};
 
+/// A participating medium with the same density everywhere inside a closed boundary.
 pub struct ConstantMedium {
     boundary: Rc<dyn Shape>,
     material: Rc<dyn Material>,
-    neg_inv_density: f64,
+    density: f64,
 }
 
 impl ConstantMedium {
     pub fn new(boundary: Rc<dyn Shape>, density: f64, texture: Rc<dyn Texture>) -> Self {
         Self {
             boundary,
-            neg_inv_density: -1.0 / density,
+            density,
             material: Rc::new(Isotropic::new(texture)),
         }
     }
     pub fn from_color(boundary: Rc<dyn Shape>, density: f64, color: Color) -> Self {
         Self {
             boundary,
-            neg_inv_density: -1.0 / density,
+            density,
             material: Rc::new(Isotropic::from_color(color)),
         }
     }
-}
-
-impl Shape for ConstantMedium {
-    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
-        let enable_debug = false;
-        let debugging = enable_debug && crate::rand(rng) < 0.000_01;
 
-        let mut rec1 = match self
-            .boundary
-            .hit(ray, f64::NEG_INFINITY, f64::INFINITY, rng)
-        {
-            Some(rec) => rec,
-            None => return None,
-        };
-        let mut rec2 = match self.boundary.hit(ray, rec1.t + 0.0001, f64::INFINITY, rng) {
-            Some(rec) => rec,
-            None => return None,
-        };
-
-        if debugging {
-            eprintln!("\nt_min={}, t_max={}", rec1.t, rec2.t);
-        }
-
-        if rec1.t < t_min {
-            rec1.t = t_min;
-        }
-        if rec2.t > t_max {
-            rec2.t = t_max;
+    pub fn with_phase(
+        boundary: Rc<dyn Shape>,
+        density: f64,
+        texture: Rc<dyn Texture>,
+        phase: Rc<dyn PhaseFunction>,
+    ) -> Self {
+        Self {
+            boundary,
+            density,
+            material: Rc::new(Volume::new(texture, phase)),
         }
+    }
 
-        if rec1.t >= rec2.t {
-            return None;
+    pub fn from_material(
+        boundary: Rc<dyn Shape>,
+        density: f64,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        Self {
+            boundary,
+            density,
+            material,
         }
+    }
+}
 
-        if rec1.t < 0.0 {
-            rec1.t = 0.0;
-        }
+impl Medium for ConstantMedium {
+    fn intervals(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Vec<(f64, f64)> {
+        medium::boundary_intervals(self.boundary.as_ref(), ray, t_min, t_max, rng)
+    }
 
+    fn collide_in(&self, ray: &Ray, t0: f64, t1: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
         let ray_length = ray.direction().norm();
-        let distance_inside_boundary = (rec2.t - rec1.t) * ray_length;
-        let hit_distance = self.neg_inv_density * crate::rand(rng).ln();
-
+        let distance_inside_boundary = (t1 - t0) * ray_length;
+        let hit_distance = -f64::ln(1.0 - rand(rng)) / self.density;
         if hit_distance > distance_inside_boundary {
             return None;
         }
 
-        let mut rec = HitRecord::default();
-        rec.t = rec1.t + hit_distance / ray_length;
-        rec.point = ray.at(rec.t);
+        let t = t0 + hit_distance / ray_length;
+        Some(HitRecord::in_medium(ray, t, Rc::clone(&self.material)))
+    }
+
+    fn transmittance_in(&self, ray: &Ray, t0: f64, t1: f64, _: &mut ThreadRng) -> f64 {
+        f64::exp(-self.density * (t1 - t0) * ray.direction().norm())
+    }
+}
 
-        if debugging {
-            eprintln!("hit_distance = {}", hit_distance);
-            eprintln!("rec.t = {}", rec.t);
-            eprintln!("rec.point = {}", rec.point);
-        }
+impl Particles for ConstantMedium {
+    fn density(&self, _: Point3) -> f64 {
+        self.density
+    }
 
-        rec.normal = Vec3::new(1.0, 0.0, 0.0);
-        rec.material = Rc::clone(&self.material);
+    fn material(&self) -> Rc<dyn Material> {
+        Rc::clone(&self.material)
+    }
+}
 
-        Some(rec)
+impl Shape for ConstantMedium {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+        self.collide(ray, t_min, t_max, rng)
     }
 
     fn bounding_box(&self, time0: f64, time1: f64) -> Option<Rc<AABB>> {
         self.boundary.bounding_box(time0, time1)
     }
+
+    fn media<'a>(
+        &'a self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut ThreadRng,
+        segments: &mut Vec<Segment<'a>>,
+    ) {
+        for (t0, t1) in self.intervals(ray, t_min, t_max, rng) {
+            segments.push(Segment {
+                t0,
+                t1,
+                particles: self,
+                ray: *ray,
+            });
+        }
+    }
 }
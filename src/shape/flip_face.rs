@@ -0,0 +1,44 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::{medium::Segment, HitRecord, Shape};
+use crate::aabb::AABB;
+use crate::ray::Ray;
+use crate::vec3::Point3;
+
+pub struct FlipFace {
+    shape: Rc<dyn Shape>,
+}
+
+impl FlipFace {
+    pub fn new(shape: Rc<dyn Shape>) -> Self {
+        Self { shape }
+    }
+}
+
+impl Shape for FlipFace {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+        let mut rec = self.shape.hit(ray, t_min, t_max, rng)?;
+        rec.front_face = !rec.front_face;
+        Some(rec)
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Rc<AABB>> {
+        self.shape.bounding_box(time0, time1)
+    }
+
+    fn media<'a>(
+        &'a self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut ThreadRng,
+        segments: &mut Vec<Segment<'a>>,
+    ) {
+        self.shape.media(ray, t_min, t_max, rng, segments);
+    }
+
+    fn random_point(&self, rng: &mut ThreadRng) -> Option<(Point3, f64)> {
+        self.shape.random_point(rng)
+    }
+}
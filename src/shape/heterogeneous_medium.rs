@@ -0,0 +1,143 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::medium::{self, Medium, Particles, Segment};
+use super::{HitRecord, Shape};
+use crate::aabb::AABB;
+use crate::density::Density;
+use crate::material::{isotropic::Isotropic, volume::Volume, Material};
+use crate::phase::PhaseFunction;
+use crate::rand;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::vec3::Point3;
+
+/// A participating medium inside a boundary shape, with a density that varies from point to
+/// point. Collisions are found with delta tracking against the maximum density.
+pub struct HeterogeneousMedium {
+    boundary: Rc<dyn Shape>,
+    density: Rc<dyn Density>,
+    material: Rc<dyn Material>,
+}
+
+impl HeterogeneousMedium {
+    pub fn new(
+        boundary: Rc<dyn Shape>,
+        density: Rc<dyn Density>,
+        texture: Rc<dyn Texture>,
+    ) -> Self {
+        Self {
+            boundary,
+            density,
+            material: Rc::new(Isotropic::new(texture)),
+        }
+    }
+
+    pub fn with_phase(
+        boundary: Rc<dyn Shape>,
+        density: Rc<dyn Density>,
+        texture: Rc<dyn Texture>,
+        phase: Rc<dyn PhaseFunction>,
+    ) -> Self {
+        Self {
+            boundary,
+            density,
+            material: Rc::new(Volume::new(texture, phase)),
+        }
+    }
+
+    /// A medium with any volume material, for example an emissive `Volume` for fire.
+    pub fn from_material(
+        boundary: Rc<dyn Shape>,
+        density: Rc<dyn Density>,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        Self {
+            boundary,
+            density,
+            material,
+        }
+    }
+}
+
+impl Medium for HeterogeneousMedium {
+    fn intervals(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Vec<(f64, f64)> {
+        medium::boundary_intervals(self.boundary.as_ref(), ray, t_min, t_max, rng)
+    }
+
+    fn collide_in(&self, ray: &Ray, t0: f64, t1: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+        let max_density = self.density.max_density();
+        if max_density <= 0.0 {
+            return None;
+        }
+
+        let ray_length = ray.direction().norm();
+        let mut t = t0;
+        loop {
+            t -= f64::ln(1.0 - rand(rng)) / (max_density * ray_length);
+            if t >= t1 {
+                return None;
+            }
+            if self.density.density(ray.at(t)) > rand(rng) * max_density {
+                return Some(HitRecord::in_medium(ray, t, Rc::clone(&self.material)));
+            }
+        }
+    }
+
+    // Ratio tracking, which weights each tentative collision instead of stopping at it.
+    fn transmittance_in(&self, ray: &Ray, t0: f64, t1: f64, rng: &mut ThreadRng) -> f64 {
+        let max_density = self.density.max_density();
+        if max_density <= 0.0 {
+            return 1.0;
+        }
+
+        let ray_length = ray.direction().norm();
+        let mut transmittance = 1.0;
+        let mut t = t0;
+        loop {
+            t -= f64::ln(1.0 - rand(rng)) / (max_density * ray_length);
+            if t >= t1 {
+                return transmittance;
+            }
+            transmittance *= 1.0 - self.density.density(ray.at(t)) / max_density;
+        }
+    }
+}
+
+impl Particles for HeterogeneousMedium {
+    fn density(&self, point: Point3) -> f64 {
+        self.density.density(point)
+    }
+
+    fn material(&self) -> Rc<dyn Material> {
+        Rc::clone(&self.material)
+    }
+}
+
+impl Shape for HeterogeneousMedium {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+        self.collide(ray, t_min, t_max, rng)
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Rc<AABB>> {
+        self.boundary.bounding_box(time0, time1)
+    }
+
+    fn media<'a>(
+        &'a self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut ThreadRng,
+        segments: &mut Vec<Segment<'a>>,
+    ) {
+        for (t0, t1) in self.intervals(ray, t_min, t_max, rng) {
+            segments.push(Segment {
+                t0,
+                t1,
+                particles: self,
+                ray: *ray,
+            });
+        }
+    }
+}
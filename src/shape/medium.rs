@@ -0,0 +1,78 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::{HitRecord, Shape};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vec3::Point3;
+
+/// A participating medium filling the inside of a closed boundary.
+pub trait Medium: Shape {
+    /// The parts of the ray between `t_min` and `t_max` that are inside the medium, in order.
+    fn intervals(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Vec<(f64, f64)>;
+
+    /// Samples the first collision with a particle between `t0` and `t1`, which lie inside one
+    /// interval.
+    fn collide_in(&self, ray: &Ray, t0: f64, t1: f64, rng: &mut ThreadRng) -> Option<HitRecord>;
+
+    /// Estimates the fraction of light passing between `t0` and `t1`, which lie inside one
+    /// interval.
+    fn transmittance_in(&self, ray: &Ray, t0: f64, t1: f64, rng: &mut ThreadRng) -> f64;
+
+    fn collide(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+        self.intervals(ray, t_min, t_max, rng)
+            .into_iter()
+            .find_map(|(t0, t1)| self.collide_in(ray, t0, t1, rng))
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> f64 {
+        self.intervals(ray, t_min, t_max, rng)
+            .into_iter()
+            .map(|(t0, t1)| self.transmittance_in(ray, t0, t1, rng))
+            .product()
+    }
+}
+
+/// The particles of a single medium, for sampling the light they scatter toward a ray.
+pub trait Particles {
+    /// The density at a point inside the medium.
+    fn density(&self, point: Point3) -> f64;
+
+    /// The material particles of the medium scatter light with.
+    fn material(&self) -> Rc<dyn Material>;
+}
+
+/// A part of a ray between `t0` and `t1` that passes through the particles of one medium.
+/// `ray` is the ray in the space of the medium, with the same t as the ray in world space.
+#[derive(Copy, Clone)]
+pub struct Segment<'a> {
+    pub t0: f64,
+    pub t1: f64,
+    pub particles: &'a dyn Particles,
+    pub ray: Ray,
+}
+
+/// Finds the parts of the ray between `t_min` and `t_max` that are inside `boundary`. Every
+/// crossing along the whole line is found, and the inside lies between the first and second,
+/// the third and fourth and so on. This works for non-convex boundaries and for rays that start
+/// inside, without relying on the orientation of the surface normals.
+pub(super) fn boundary_intervals(
+    boundary: &dyn Shape,
+    ray: &Ray,
+    t_min: f64,
+    t_max: f64,
+    rng: &mut ThreadRng,
+) -> Vec<(f64, f64)> {
+    let mut crossings = Vec::new();
+    let mut t = f64::NEG_INFINITY;
+    while let Some(rec) = boundary.hit(ray, t, f64::INFINITY, rng) {
+        crossings.push(rec.t);
+        t = rec.t + 0.0001;
+    }
+
+    crossings
+        .chunks_exact(2)
+        .map(|pair| (f64::max(pair[0], t_min), f64::min(pair[1], t_max)))
+        .filter(|(t0, t1)| t0 < t1)
+        .collect()
+}
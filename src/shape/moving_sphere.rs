@@ -1,7 +1,7 @@
 use rand::rngs::ThreadRng;
 use std::rc::Rc;
 
-use super::{HitRecord, Shape};
+use super::{sphere, HitRecord, Shape};
 use crate::aabb::AABB;
 use crate::material::Material;
 use crate::ray::Ray;
@@ -68,6 +68,9 @@ impl Shape for MovingSphere {
         let outward_normal = (rec.point - self.center(ray.time())) / self.radius;
         rec.set_face_normal(ray, outward_normal);
         rec.material = Rc::clone(&self.material);
+        let (dpdu, dpdv) = sphere::tangents(outward_normal, self.radius);
+        rec.dpdu = dpdu;
+        rec.dpdv = dpdv;
 
         return Some(rec);
     }
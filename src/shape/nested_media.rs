@@ -0,0 +1,127 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::medium::{Medium, Segment};
+use super::{HitRecord, Shape};
+use crate::aabb::AABB;
+use crate::ray::Ray;
+
+/// Overlapping participating media where only one medium is present at each point. Where media
+/// overlap the one with the highest priority wins, and on equal priorities the one added first.
+/// This carves a clear bubble out of fog, or puts a liquid inside the air of a glass.
+pub struct NestedMedia {
+    media: Vec<(Rc<dyn Medium>, i32)>,
+}
+
+impl NestedMedia {
+    pub fn new() -> Self {
+        Self { media: Vec::new() }
+    }
+
+    pub fn add(&mut self, medium: Rc<dyn Medium>, priority: i32) {
+        self.media.push((medium, priority));
+    }
+
+    // Splits the ray into parts that each belong to the winning medium.
+    fn segments(
+        &self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut ThreadRng,
+    ) -> Vec<(f64, f64, usize)> {
+        let mut events = Vec::new();
+        for (i, (medium, _)) in self.media.iter().enumerate() {
+            for (t0, t1) in medium.intervals(ray, t_min, t_max, rng) {
+                events.push((t0, i, true));
+                events.push((t1, i, false));
+            }
+        }
+        events.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut active = vec![false; self.media.len()];
+        let mut segments: Vec<(f64, f64, usize)> = Vec::new();
+        for (k, &(t, i, entering)) in events.iter().enumerate() {
+            active[i] = entering;
+            let next = match events.get(k + 1) {
+                Some(&(next, _, _)) if next > t => next,
+                _ => continue,
+            };
+
+            let owner = (0..self.media.len())
+                .filter(|&j| active[j])
+                .max_by_key(|&j| (self.media[j].1, std::cmp::Reverse(j)));
+            if let Some(owner) = owner {
+                match segments.last_mut() {
+                    Some(last) if last.2 == owner && last.1 == t => last.1 = next,
+                    _ => segments.push((t, next, owner)),
+                }
+            }
+        }
+        segments
+    }
+}
+
+impl Default for NestedMedia {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Medium for NestedMedia {
+    fn intervals(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Vec<(f64, f64)> {
+        let mut intervals: Vec<(f64, f64)> = Vec::new();
+        for (t0, t1, _) in self.segments(ray, t_min, t_max, rng) {
+            match intervals.last_mut() {
+                Some(last) if last.1 == t0 => last.1 = t1,
+                _ => intervals.push((t0, t1)),
+            }
+        }
+        intervals
+    }
+
+    fn collide_in(&self, ray: &Ray, t0: f64, t1: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+        self.segments(ray, t0, t1, rng)
+            .into_iter()
+            .find_map(|(t0, t1, i)| self.media[i].0.collide_in(ray, t0, t1, rng))
+    }
+
+    fn transmittance_in(&self, ray: &Ray, t0: f64, t1: f64, rng: &mut ThreadRng) -> f64 {
+        self.segments(ray, t0, t1, rng)
+            .into_iter()
+            .map(|(t0, t1, i)| self.media[i].0.transmittance_in(ray, t0, t1, rng))
+            .product()
+    }
+}
+
+impl Shape for NestedMedia {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+        self.collide(ray, t_min, t_max, rng)
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Rc<AABB>> {
+        let mut output_box: Option<Rc<AABB>> = None;
+        for (medium, _) in &self.media {
+            let tmp = medium.bounding_box(time0, time1)?;
+            output_box = Some(match output_box {
+                Some(output_box) => Rc::new(AABB::surrounding_box(&output_box, &tmp)),
+                None => tmp,
+            });
+        }
+        output_box
+    }
+
+    fn media<'a>(
+        &'a self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut ThreadRng,
+        segments: &mut Vec<Segment<'a>>,
+    ) {
+        // Each medium reports its particles on the parts of the ray it wins.
+        for (t0, t1, i) in self.segments(ray, t_min, t_max, rng) {
+            self.media[i].0.media(ray, t0, t1, rng, segments);
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use rand::rngs::ThreadRng;
 use std::rc::Rc;
 
-use super::{HitRecord, Shape};
+use super::{medium::Segment, HitRecord, Shape};
 use crate::aabb::AABB;
 use crate::ray::Ray;
 use crate::vec3::{Point3, Vec3};
@@ -60,8 +60,8 @@ impl RotateY {
     }
 }
 
-impl Shape for RotateY {
-    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+impl RotateY {
+    fn rotate(&self, ray: &Ray) -> Ray {
         let mut origin = ray.origin();
         let mut direction = ray.direction();
 
@@ -71,7 +71,21 @@ impl Shape for RotateY {
         direction[0] = self.cos_theta * ray.direction()[0] - self.sin_theta * ray.direction()[2];
         direction[2] = self.sin_theta * ray.direction()[0] + self.cos_theta * ray.direction()[2];
 
-        let rotated = Ray::new(origin, direction, ray.time());
+        Ray::new(origin, direction, ray.time())
+    }
+
+    fn rotate_back(&self, v: Vec3) -> Vec3 {
+        Vec3::new(
+            self.cos_theta * v.x() + self.sin_theta * v.z(),
+            v.y(),
+            -self.sin_theta * v.x() + self.cos_theta * v.z(),
+        )
+    }
+}
+
+impl Shape for RotateY {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+        let rotated = self.rotate(ray);
 
         let mut rec = match self.shape.hit(&rotated, t_min, t_max, rng) {
             Some(rec) => rec,
@@ -89,6 +103,8 @@ impl Shape for RotateY {
 
         rec.point = point;
         rec.set_face_normal(&rotated, normal);
+        rec.dpdu = self.rotate_back(rec.dpdu);
+        rec.dpdv = self.rotate_back(rec.dpdv);
 
         Some(rec)
     }
@@ -99,4 +115,21 @@ impl Shape for RotateY {
             None => None,
         }
     }
+
+    fn media<'a>(
+        &'a self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut ThreadRng,
+        segments: &mut Vec<Segment<'a>>,
+    ) {
+        self.shape
+            .media(&self.rotate(ray), t_min, t_max, rng, segments);
+    }
+
+    fn random_point(&self, rng: &mut ThreadRng) -> Option<(Point3, f64)> {
+        let (point, area) = self.shape.random_point(rng)?;
+        Some((self.rotate_back(point), area))
+    }
 }
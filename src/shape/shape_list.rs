@@ -1,7 +1,7 @@
 use rand::rngs::ThreadRng;
 use std::rc::Rc;
 
-use super::{HitRecord, Shape};
+use super::{medium::Segment, HitRecord, Shape};
 use crate::aabb::AABB;
 use crate::ray::Ray;
 
@@ -68,4 +68,17 @@ impl Shape for ShapeList {
 
         Some(output_box)
     }
+
+    fn media<'a>(
+        &'a self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut ThreadRng,
+        segments: &mut Vec<Segment<'a>>,
+    ) {
+        for shape in &self.shapes {
+            shape.media(ray, t_min, t_max, rng, segments);
+        }
+    }
 }
@@ -40,6 +40,22 @@ impl Sphere {
     }
 }
 
+// The derivatives of the point on a sphere with the given radius and outward normal, with
+// respect to the uv-coordinates of `Sphere::uv`.
+pub(super) fn tangents(normal: Vec3, radius: f64) -> (Vec3, Vec3) {
+    use std::f64::consts::PI;
+    let (x, y, z) = (normal.x(), normal.y(), normal.z());
+    let dpdu = 2.0 * PI * radius * Vec3::new(z, 0.0, -x);
+
+    let sin_theta = f64::sqrt(x * x + z * z);
+    let dpdv = if sin_theta > 1e-8 {
+        PI * radius * Vec3::new(-x * y / sin_theta, sin_theta, -y * z / sin_theta)
+    } else {
+        Vec3::cross(normal, Vec3::new(1.0, 0.0, 0.0))
+    };
+    (dpdu, dpdv)
+}
+
 impl Shape for Sphere {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, _: &mut ThreadRng) -> Option<HitRecord> {
         let oc = ray.origin() - self.center;
@@ -70,6 +86,9 @@ impl Shape for Sphere {
         let (u, v) = self.uv(&outward_normal);
         rec.u = u;
         rec.v = v;
+        let (dpdu, dpdv) = tangents(outward_normal, self.radius);
+        rec.dpdu = dpdu;
+        rec.dpdv = dpdv;
 
         return Some(rec);
     }
@@ -80,4 +99,10 @@ impl Shape for Sphere {
             self.center + Vec3::new(self.radius, self.radius, self.radius),
         )))
     }
+
+    fn random_point(&self, rng: &mut ThreadRng) -> Option<(Point3, f64)> {
+        let point = self.center + self.radius * Vec3::rand_unit(rng);
+        let area = 4.0 * std::f64::consts::PI * self.radius * self.radius;
+        Some((point, area))
+    }
 }
@@ -0,0 +1,171 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::{medium::Segment, HitRecord, Shape};
+use crate::aabb::AABB;
+use crate::matrix::Matrix;
+use crate::quaternion::Quaternion;
+use crate::ray::Ray;
+use crate::vec3::{Point3, Vec3};
+
+/// A shape moved by an affine transform. The builders apply their transform after the ones
+/// before, so `Transform::new(shape).rotate(axis, 30.0).translate(offset)` rotates first.
+/// Transforms squashing the shape flat, like a scale by zero, are allowed but never hit.
+pub struct Transform {
+    shape: Rc<dyn Shape>,
+    matrix: Matrix,
+    // `None` when the matrix can't be inverted.
+    space: Option<LocalSpace>,
+}
+
+impl Transform {
+    pub fn new(shape: Rc<dyn Shape>) -> Self {
+        Self::from_matrix(shape, Matrix::identity())
+    }
+
+    pub fn from_matrix(shape: Rc<dyn Shape>, matrix: Matrix) -> Self {
+        Self {
+            shape,
+            matrix,
+            space: LocalSpace::new(matrix),
+        }
+    }
+
+    pub fn then(self, matrix: Matrix) -> Self {
+        Self::from_matrix(self.shape, matrix * self.matrix)
+    }
+
+    pub fn translate(self, offset: Vec3) -> Self {
+        self.then(Matrix::translation(offset))
+    }
+
+    /// Rotates by `angle` degrees around `axis` through the origin.
+    pub fn rotate(self, axis: Vec3, angle: f64) -> Self {
+        self.then(Matrix::rotation(axis, angle))
+    }
+
+    pub fn rotate_quaternion(self, rotation: Quaternion) -> Self {
+        self.then(Matrix::from_quaternion(rotation))
+    }
+
+    pub fn scale(self, factors: Vec3) -> Self {
+        self.then(Matrix::scaling(factors))
+    }
+}
+
+impl Shape for Transform {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+        self.space
+            .as_ref()?
+            .hit(&*self.shape, ray, t_min, t_max, rng)
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Rc<AABB>> {
+        let aabb = self.shape.bounding_box(time0, time1)?;
+        Some(Rc::new(transform_box(&self.matrix, &aabb)))
+    }
+
+    fn media<'a>(
+        &'a self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut ThreadRng,
+        segments: &mut Vec<Segment<'a>>,
+    ) {
+        if let Some(space) = &self.space {
+            self.shape
+                .media(&space.ray(ray), t_min, t_max, rng, segments);
+        }
+    }
+
+    /// Only available for transforms keeping shapes similar, i.e. without non-uniform scaling or
+    /// shearing, as only then the area is known.
+    fn random_point(&self, rng: &mut ThreadRng) -> Option<(Point3, f64)> {
+        let [x, y, z] = [0, 1, 2].map(|i| {
+            let mut axis = Vec3::default();
+            axis[i] = 1.0;
+            self.matrix.transform_vector(axis)
+        });
+        let scale = x.norm_squared();
+        let tolerance = 1e-9 * scale;
+        let similar = [y, z]
+            .iter()
+            .all(|a| (a.norm_squared() - scale).abs() < tolerance)
+            && [(x, y), (y, z), (z, x)]
+                .iter()
+                .all(|&(a, b)| Vec3::dot(a, b).abs() < tolerance);
+        if !similar {
+            return None;
+        }
+
+        let (point, area) = self.shape.random_point(rng)?;
+        Some((self.matrix.transform_point(point), scale * area))
+    }
+}
+
+/// The space of a transformed shape, with the matrices moving rays into it and hits out of it.
+pub(crate) struct LocalSpace {
+    matrix: Matrix,
+    inverse: Matrix,
+    // Normals follow the inverse transpose, which keeps them on the side of the ray.
+    normal_matrix: Matrix,
+}
+
+impl LocalSpace {
+    /// Returns `None` when `matrix` squashes space flat.
+    pub(crate) fn new(matrix: Matrix) -> Option<Self> {
+        let inverse = matrix.inverse()?;
+        Some(Self {
+            matrix,
+            inverse,
+            normal_matrix: inverse.transpose(),
+        })
+    }
+
+    /// The ray in this space. The direction isn't normalized, so t is the same in both spaces.
+    pub(crate) fn ray(&self, ray: &Ray) -> Ray {
+        Ray::new(
+            self.inverse.transform_point(ray.origin()),
+            self.inverse.transform_vector(ray.direction()),
+            ray.time(),
+        )
+        .with_wavelength(ray.wavelength())
+    }
+
+    pub(crate) fn hit(
+        &self,
+        shape: &dyn Shape,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut ThreadRng,
+    ) -> Option<HitRecord> {
+        let mut rec = shape.hit(&self.ray(ray), t_min, t_max, rng)?;
+        rec.point = self.matrix.transform_point(rec.point);
+        rec.normal = self.normal_matrix.transform_vector(rec.normal).normalized();
+        rec.dpdu = self.matrix.transform_vector(rec.dpdu);
+        rec.dpdv = self.matrix.transform_vector(rec.dpdv);
+        Some(rec)
+    }
+}
+
+/// The box around the corners of `aabb` after transforming them.
+pub(crate) fn transform_box(matrix: &Matrix, aabb: &AABB) -> AABB {
+    let mut minimum = Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+    let mut maximum = Point3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for corner in 0..8 {
+        let mut point = aabb.minimum();
+        for i in 0..3 {
+            if corner & (1 << i) != 0 {
+                point[i] = aabb.maximum()[i];
+            }
+        }
+        let point = matrix.transform_point(point);
+        for i in 0..3 {
+            minimum[i] = minimum[i].min(point[i]);
+            maximum[i] = maximum[i].max(point[i]);
+        }
+    }
+    AABB::new(minimum, maximum)
+}
@@ -1,10 +1,10 @@
 use rand::rngs::ThreadRng;
 use std::rc::Rc;
 
-use super::{HitRecord, Shape};
+use super::{medium::Segment, HitRecord, Shape};
 use crate::aabb::AABB;
 use crate::ray::Ray;
-use crate::vec3::Vec3;
+use crate::vec3::{Point3, Vec3};
 
 pub struct Translate {
     shape: Rc<dyn Shape>,
@@ -15,11 +15,15 @@ impl Translate {
     pub fn new(shape: Rc<dyn Shape>, offset: Vec3) -> Self {
         Self { shape, offset }
     }
+
+    fn move_back(&self, ray: &Ray) -> Ray {
+        Ray::new(ray.origin() - self.offset, ray.direction(), ray.time())
+    }
 }
 
 impl Shape for Translate {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
-        let moved = Ray::new(ray.origin() - self.offset, ray.direction(), ray.time());
+        let moved = self.move_back(ray);
         let mut rec = match self.shape.hit(&moved, t_min, t_max, rng) {
             Some(rec) => rec,
             None => return None,
@@ -40,4 +44,21 @@ impl Shape for Translate {
             bounding_box.maximum() + self.offset,
         )))
     }
+
+    fn media<'a>(
+        &'a self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rng: &mut ThreadRng,
+        segments: &mut Vec<Segment<'a>>,
+    ) {
+        self.shape
+            .media(&self.move_back(ray), t_min, t_max, rng, segments);
+    }
+
+    fn random_point(&self, rng: &mut ThreadRng) -> Option<(Point3, f64)> {
+        let (point, area) = self.shape.random_point(rng)?;
+        Some((point + self.offset, area))
+    }
 }
@@ -0,0 +1,255 @@
+use rand::rngs::ThreadRng;
+use std::rc::Rc;
+
+use super::{bvh_node::BvhNode, shape_list::ShapeList, HitRecord, Shape};
+use crate::aabb::AABB;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::rand;
+use crate::ray::Ray;
+use crate::vec3::{Color, Point3, Vec3};
+
+/// The vertex buffers of a triangle mesh. Each triangle is three indices into the vertices, and
+/// the normals, uv-coordinates and colors, when given, have one entry per vertex.
+#[derive(Default)]
+pub struct Mesh {
+    pub positions: Vec<Point3>,
+    pub normals: Option<Vec<Vec3>>,
+    pub uvs: Option<Vec<(f64, f64)>>,
+    pub colors: Option<Vec<Color>>,
+    pub indices: Vec<[usize; 3]>,
+}
+
+/// A mesh of triangles sharing their vertices, with its own bounding volume hierarchy. Vertex
+/// normals are interpolated for smooth shading, and without uv-coordinates the barycentric
+/// coordinates are used instead. Vertex colors are interpolated into the hit, for the
+/// `VertexColors` texture.
+pub struct TriangleMesh {
+    bvh: BvhNode,
+    triangles: Vec<Rc<Triangle>>,
+    // The running total of the triangle areas, to pick triangles by area.
+    areas: Vec<f64>,
+}
+
+impl TriangleMesh {
+    pub fn new(mesh: Mesh, material: Rc<dyn Material>, rng: &mut ThreadRng) -> Self {
+        assert!(
+            !mesh.indices.is_empty(),
+            "TriangleMesh needs at least one triangle"
+        );
+        let vertices = mesh.positions.len();
+        assert!(
+            mesh.indices.iter().flatten().all(|&i| i < vertices),
+            "TriangleMesh index out of bounds"
+        );
+        assert!(
+            mesh.normals.iter().all(|n| n.len() == vertices),
+            "TriangleMesh needs one normal per vertex"
+        );
+        assert!(
+            mesh.uvs.iter().all(|uv| uv.len() == vertices),
+            "TriangleMesh needs one uv-coordinate per vertex"
+        );
+        assert!(
+            mesh.colors.iter().all(|c| c.len() == vertices),
+            "TriangleMesh needs one color per vertex"
+        );
+
+        let mesh = Rc::new(mesh);
+        let triangles: Vec<Rc<Triangle>> = (0..mesh.indices.len())
+            .map(|index| {
+                Rc::new(Triangle {
+                    mesh: Rc::clone(&mesh),
+                    index,
+                    material: Rc::clone(&material),
+                })
+            })
+            .collect();
+
+        let mut total = 0.0;
+        let areas = triangles
+            .iter()
+            .map(|triangle| {
+                total += triangle.area();
+                total
+            })
+            .collect();
+
+        let mut list = ShapeList::default();
+        for triangle in &triangles {
+            list.add(Rc::clone(triangle) as Rc<dyn Shape>);
+        }
+
+        Self {
+            bvh: BvhNode::new(list, 0.0, 1.0, rng),
+            triangles,
+            areas,
+        }
+    }
+}
+
+impl Shape for TriangleMesh {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rng: &mut ThreadRng) -> Option<HitRecord> {
+        self.bvh.hit(ray, t_min, t_max, rng)
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Rc<AABB>> {
+        self.bvh.bounding_box(time0, time1)
+    }
+
+    fn random_point(&self, rng: &mut ThreadRng) -> Option<(Point3, f64)> {
+        let total = *self.areas.last()?;
+        let target = rand(rng) * total;
+        let index = self
+            .areas
+            .partition_point(|&area| area < target)
+            .min(self.triangles.len() - 1);
+        let (point, _) = self.triangles[index].random_point(rng)?;
+        Some((point, total))
+    }
+}
+
+struct Triangle {
+    mesh: Rc<Mesh>,
+    index: usize,
+    material: Rc<dyn Material>,
+}
+
+impl Triangle {
+    fn vertices(&self) -> [Point3; 3] {
+        let [i0, i1, i2] = self.mesh.indices[self.index];
+        let positions = &self.mesh.positions;
+        [positions[i0], positions[i1], positions[i2]]
+    }
+
+    fn area(&self) -> f64 {
+        let [p0, p1, p2] = self.vertices();
+        0.5 * Vec3::cross(p1 - p0, p2 - p0).norm()
+    }
+}
+
+impl Shape for Triangle {
+    // The watertight ray-triangle test by Woop, Benthin and Wald, which never lets a ray slip
+    // through the shared edge of two triangles.
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, _: &mut ThreadRng) -> Option<HitRecord> {
+        let direction = ray.direction();
+        let kz = (0..3)
+            .max_by(|&a, &b| direction[a].abs().total_cmp(&direction[b].abs()))
+            .unwrap();
+        let (mut kx, mut ky) = ((kz + 1) % 3, (kz + 2) % 3);
+        if direction[kz] < 0.0 {
+            std::mem::swap(&mut kx, &mut ky);
+        }
+
+        let shear_x = direction[kx] / direction[kz];
+        let shear_y = direction[ky] / direction[kz];
+        let shear_z = 1.0 / direction[kz];
+
+        let [p0, p1, p2] = self.vertices();
+        let [a, b, c] = [p0 - ray.origin(), p1 - ray.origin(), p2 - ray.origin()];
+        let (ax, ay) = (a[kx] - shear_x * a[kz], a[ky] - shear_y * a[kz]);
+        let (bx, by) = (b[kx] - shear_x * b[kz], b[ky] - shear_y * b[kz]);
+        let (cx, cy) = (c[kx] - shear_x * c[kz], c[ky] - shear_y * c[kz]);
+
+        let u = cx * by - cy * bx;
+        let v = ax * cy - ay * cx;
+        let w = bx * ay - by * ax;
+        if (u < 0.0 || v < 0.0 || w < 0.0) && (u > 0.0 || v > 0.0 || w > 0.0) {
+            return None;
+        }
+
+        let det = u + v + w;
+        if det == 0.0 {
+            return None;
+        }
+
+        let t = (u * shear_z * a[kz] + v * shear_z * b[kz] + w * shear_z * c[kz]) / det;
+        if t < t_min || t > t_max {
+            return None;
+        }
+
+        let barycentric = [u / det, v / det, w / det];
+        let [i0, i1, i2] = self.mesh.indices[self.index];
+        let interpolate = |values: [Vec3; 3]| {
+            values[0] * barycentric[0] + values[1] * barycentric[1] + values[2] * barycentric[2]
+        };
+
+        let geometric_normal = Vec3::cross(p1 - p0, p2 - p0).normalized();
+        let shading_normal = match &self.mesh.normals {
+            Some(normals) => {
+                let normal = interpolate([normals[i0], normals[i1], normals[i2]]).normalized();
+                if Vec3::dot(normal, geometric_normal) < 0.0 {
+                    -normal
+                } else {
+                    normal
+                }
+            }
+            None => geometric_normal,
+        };
+
+        let uvs = match &self.mesh.uvs {
+            Some(uvs) => [uvs[i0], uvs[i1], uvs[i2]],
+            None => [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)],
+        };
+        let uv = interpolate(uvs.map(|(u, v)| Vec3::new(u, v, 0.0)));
+        let color = self
+            .mesh
+            .colors
+            .as_ref()
+            .map(|colors| interpolate([colors[i0], colors[i1], colors[i2]]));
+
+        // The tangents follow the uv-coordinates, unless they don't span the triangle.
+        let (du02, dv02) = (uvs[0].0 - uvs[2].0, uvs[0].1 - uvs[2].1);
+        let (du12, dv12) = (uvs[1].0 - uvs[2].0, uvs[1].1 - uvs[2].1);
+        let (dp02, dp12) = (p0 - p2, p1 - p2);
+        let uv_det = du02 * dv12 - dv02 * du12;
+        let (dpdu, dpdv) = if uv_det.abs() > 1e-12 {
+            (
+                (dv12 * dp02 - dv02 * dp12) / uv_det,
+                (du02 * dp12 - du12 * dp02) / uv_det,
+            )
+        } else {
+            let onb = Onb::from_w(geometric_normal);
+            (onb.u(), onb.v())
+        };
+
+        // The side is decided by the actual surface, so the shading normal can't flip it.
+        let front_face = Vec3::dot(direction, geometric_normal) < 0.0;
+        Some(HitRecord {
+            point: ray.at(t),
+            normal: if front_face {
+                shading_normal
+            } else {
+                -shading_normal
+            },
+            t,
+            front_face,
+            material: Rc::clone(&self.material),
+            u: uv.x(),
+            v: uv.y(),
+            dpdu,
+            dpdv,
+            color,
+            medium: false,
+        })
+    }
+
+    fn bounding_box(&self, _: f64, _: f64) -> Option<Rc<AABB>> {
+        let [p0, p1, p2] = self.vertices();
+        let mut minimum = Point3::default();
+        let mut maximum = Point3::default();
+        for i in 0..3 {
+            minimum[i] = p0[i].min(p1[i]).min(p2[i]) - 0.0001;
+            maximum[i] = p0[i].max(p1[i]).max(p2[i]) + 0.0001;
+        }
+        Some(Rc::new(AABB::new(minimum, maximum)))
+    }
+
+    fn random_point(&self, rng: &mut ThreadRng) -> Option<(Point3, f64)> {
+        let [p0, p1, p2] = self.vertices();
+        let root = rand(rng).sqrt();
+        let r = rand(rng);
+        let point = p0 + root * (1.0 - r) * (p1 - p0) + root * r * (p2 - p0);
+        Some((point, self.area()))
+    }
+}
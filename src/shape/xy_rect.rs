@@ -4,6 +4,7 @@ use std::rc::Rc;
 use super::{HitRecord, Shape};
 use crate::aabb::AABB;
 use crate::material::Material;
+use crate::rand_between;
 use crate::ray::Ray;
 use crate::vec3::{Point3, Vec3};
 
@@ -46,6 +47,8 @@ impl Shape for XyRect {
         rec.u = (x - self.x0) / (self.x1 - self.x0);
         rec.v = (y - self.y0) / (self.y1 - self.y0);
         rec.t = t;
+        rec.dpdu = Vec3::new(self.x1 - self.x0, 0.0, 0.0);
+        rec.dpdv = Vec3::new(0.0, self.y1 - self.y0, 0.0);
         let outward_normal = Vec3::new(0.0, 0.0, 1.0);
         rec.set_face_normal(ray, outward_normal);
         rec.material = Rc::clone(&self.material);
@@ -59,4 +62,14 @@ impl Shape for XyRect {
             Point3::new(self.x1, self.y1, self.k + 0.0001),
         )))
     }
+
+    fn random_point(&self, rng: &mut ThreadRng) -> Option<(Point3, f64)> {
+        let point = Point3::new(
+            rand_between(self.x0, self.x1, rng),
+            rand_between(self.y0, self.y1, rng),
+            self.k,
+        );
+        let area = (self.x1 - self.x0) * (self.y1 - self.y0);
+        Some((point, area))
+    }
 }
@@ -4,6 +4,7 @@ use std::rc::Rc;
 use super::{HitRecord, Shape};
 use crate::aabb::AABB;
 use crate::material::Material;
+use crate::rand_between;
 use crate::ray::Ray;
 use crate::vec3::{Point3, Vec3};
 
@@ -46,6 +47,8 @@ impl Shape for XzRect {
         rec.u = (x - self.x0) / (self.x1 - self.x0);
         rec.v = (z - self.z0) / (self.z1 - self.z0);
         rec.t = t;
+        rec.dpdu = Vec3::new(self.x1 - self.x0, 0.0, 0.0);
+        rec.dpdv = Vec3::new(0.0, 0.0, self.z1 - self.z0);
         let outward_normal = Vec3::new(0.0, 1.0, 0.0);
         rec.set_face_normal(ray, outward_normal);
         rec.material = Rc::clone(&self.material);
@@ -59,4 +62,14 @@ impl Shape for XzRect {
             Point3::new(self.x1, self.k + 0.0001, self.z1),
         )))
     }
+
+    fn random_point(&self, rng: &mut ThreadRng) -> Option<(Point3, f64)> {
+        let point = Point3::new(
+            rand_between(self.x0, self.x1, rng),
+            self.k,
+            rand_between(self.z0, self.z1, rng),
+        );
+        let area = (self.x1 - self.x0) * (self.z1 - self.z0);
+        Some((point, area))
+    }
 }
@@ -4,6 +4,7 @@ use std::rc::Rc;
 use super::{HitRecord, Shape};
 use crate::aabb::AABB;
 use crate::material::Material;
+use crate::rand_between;
 use crate::ray::Ray;
 use crate::vec3::{Point3, Vec3};
 
@@ -46,6 +47,8 @@ impl Shape for YzRect {
         rec.u = (y - self.y0) / (self.y1 - self.y0);
         rec.v = (z - self.z0) / (self.z1 - self.z0);
         rec.t = t;
+        rec.dpdu = Vec3::new(0.0, self.y1 - self.y0, 0.0);
+        rec.dpdv = Vec3::new(0.0, 0.0, self.z1 - self.z0);
         let outward_normal = Vec3::new(1.0, 0.0, 0.0);
         rec.set_face_normal(ray, outward_normal);
         rec.material = Rc::clone(&self.material);
@@ -59,4 +62,14 @@ impl Shape for YzRect {
             Point3::new(self.k + 0.0001, self.y1, self.z1),
         )))
     }
+
+    fn random_point(&self, rng: &mut ThreadRng) -> Option<(Point3, f64)> {
+        let point = Point3::new(
+            self.k,
+            rand_between(self.y0, self.y1, rng),
+            rand_between(self.z0, self.z1, rng),
+        );
+        let area = (self.y1 - self.y0) * (self.z1 - self.z0);
+        Some((point, area))
+    }
 }
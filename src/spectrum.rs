@@ -0,0 +1,102 @@
+use rand::rngs::ThreadRng;
+
+use crate::rand_between;
+use crate::vec3::{Color, Vec3};
+
+pub const LAMBDA_MIN: f64 = 380.0;
+pub const LAMBDA_MAX: f64 = 780.0;
+const LAMBDA_STEP: f64 = 5.0;
+
+// The average of `xyz_to_rgb(cie_xyz(lambda))` over the visible range.
+const MEAN_RGB: [f64; 3] = [0.320_902_557, 0.253_845_202, 0.242_662_001];
+
+/// The CIE 1931 color matching functions at `lambda` nanometers, using the multi-lobe fit
+/// of Wyman, Sloan and Shirley (2013).
+pub fn cie_xyz(lambda: f64) -> Vec3 {
+    let x = 1.056 * lobe(lambda, 599.8, 37.9, 31.0) + 0.362 * lobe(lambda, 442.0, 16.0, 26.7)
+        - 0.065 * lobe(lambda, 501.1, 20.4, 26.2);
+    let y = 0.821 * lobe(lambda, 568.8, 46.9, 40.5) + 0.286 * lobe(lambda, 530.9, 16.3, 31.1);
+    let z = 1.217 * lobe(lambda, 437.0, 11.8, 36.0) + 0.681 * lobe(lambda, 459.0, 26.0, 13.8);
+    Vec3::new(x, y, z)
+}
+
+fn lobe(x: f64, mean: f64, sigma_low: f64, sigma_high: f64) -> f64 {
+    let sigma = if x < mean { sigma_low } else { sigma_high };
+    let t = (x - mean) / sigma;
+    f64::exp(-0.5 * t * t)
+}
+
+/// Converts CIE XYZ to the linear sRGB (Rec. 709 primaries, D65 white) working color space.
+pub fn xyz_to_rgb(xyz: Vec3) -> Color {
+    Color::new(
+        3.240_454_2 * xyz.x() - 1.537_138_5 * xyz.y() - 0.498_531_4 * xyz.z(),
+        -0.969_266_0 * xyz.x() + 1.876_010_8 * xyz.y() + 0.041_556_0 * xyz.z(),
+        0.055_643_4 * xyz.x() - 0.204_025_9 * xyz.y() + 1.057_225_2 * xyz.z(),
+    )
+}
+
+/// Spectral radiance of a black body at `kelvin`, for a wavelength of `lambda` nanometers.
+pub fn planck(lambda: f64, kelvin: f64) -> f64 {
+    const C: f64 = 299_792_458.0;
+    const H: f64 = 6.626_070_15e-34;
+    const K_B: f64 = 1.380_649e-23;
+
+    let lambda = lambda * 1e-9;
+    2.0 * H * C * C / (lambda.powi(5) * (f64::exp(H * C / (lambda * K_B * kelvin)) - 1.0))
+}
+
+/// The color of a black body at `kelvin`, normalized to unit luminance.
+pub fn blackbody(kelvin: f64) -> Color {
+    if kelvin <= 0.0 {
+        return Color::default();
+    }
+
+    let steps = ((LAMBDA_MAX - LAMBDA_MIN) / LAMBDA_STEP) as usize;
+    let xyz: Vec3 = (0..=steps)
+        .map(|i| {
+            let lambda = LAMBDA_MIN + i as f64 * LAMBDA_STEP;
+            cie_xyz(lambda) * planck(lambda, kelvin)
+        })
+        .sum();
+
+    let rgb = xyz_to_rgb(xyz / xyz.y());
+    Color::new(
+        f64::max(rgb.x(), 0.0),
+        f64::max(rgb.y(), 0.0),
+        f64::max(rgb.z(), 0.0),
+    )
+}
+
+/// Samples a visible wavelength uniformly, together with the RGB weight of that wavelength,
+/// normalized such that the weights average out to white.
+pub fn sample_wavelength(rng: &mut ThreadRng) -> (f64, Color) {
+    let lambda = rand_between(LAMBDA_MIN, LAMBDA_MAX, rng);
+    let rgb = xyz_to_rgb(cie_xyz(lambda));
+    let weight = Color::new(
+        rgb.x() / MEAN_RGB[0],
+        rgb.y() / MEAN_RGB[1],
+        rgb.z() / MEAN_RGB[2],
+    );
+    (lambda, weight)
+}
+
+/// Converts a spectral reflectance to RGB under an equal energy illuminant, normalized such that
+/// a reflectance of one everywhere gives white.
+pub fn reflectance_to_rgb<F: Fn(f64) -> f64>(reflectance: F) -> Color {
+    const STEP: f64 = 10.0;
+    let steps = ((LAMBDA_MAX - LAMBDA_MIN) / STEP) as usize;
+
+    let mut rgb = Color::default();
+    let mut white = Color::default();
+    for i in 0..=steps {
+        let lambda = LAMBDA_MIN + i as f64 * STEP;
+        let weight = xyz_to_rgb(cie_xyz(lambda));
+        rgb += weight * reflectance(lambda);
+        white += weight;
+    }
+    Color::new(
+        rgb.x() / white.x(),
+        rgb.y() / white.y(),
+        rgb.z() / white.z(),
+    )
+}
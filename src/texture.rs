@@ -1,10 +1,32 @@
+use crate::shape::HitRecord;
 use crate::vec3::{Color, Point3};
 
+pub mod blackbody;
+pub mod channel;
 pub mod checkers;
 pub mod image;
 pub mod perlin;
+pub mod scale;
 pub mod solid_color;
+pub mod temperature;
+pub mod vertex_colors;
 
 pub trait Texture {
     fn color(&self, u: f64, v: f64, point: Point3) -> Color;
+
+    fn value(&self, u: f64, v: f64, point: Point3) -> f64 {
+        let color = self.color(u, v, point);
+        (color.x() + color.y() + color.z()) / 3.0
+    }
+
+    /// The color at a hit, which is what materials look up. Textures needing more of the hit
+    /// than its uv-coordinates and point override it, and textures wrapping others pass it on.
+    fn color_at(&self, rec: &HitRecord) -> Color {
+        self.color(rec.u(), rec.v(), rec.point())
+    }
+
+    fn value_at(&self, rec: &HitRecord) -> f64 {
+        let color = self.color_at(rec);
+        (color.x() + color.y() + color.z()) / 3.0
+    }
 }
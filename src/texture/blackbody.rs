@@ -0,0 +1,21 @@
+use super::Texture;
+use crate::spectrum;
+use crate::vec3::{Color, Point3};
+
+pub struct Blackbody {
+    color: Color,
+}
+
+impl Blackbody {
+    pub fn new(kelvin: f64) -> Self {
+        Self {
+            color: spectrum::blackbody(kelvin),
+        }
+    }
+}
+
+impl Texture for Blackbody {
+    fn color(&self, _: f64, _: f64, _: Point3) -> Color {
+        self.color
+    }
+}
@@ -0,0 +1,31 @@
+use std::rc::Rc;
+
+use super::Texture;
+use crate::shape::HitRecord;
+use crate::vec3::{Color, Point3};
+
+/// A single color channel of another texture, as gray. Used for textures packing several
+/// parameters, like roughness in green and metalness in blue.
+pub struct Channel {
+    texture: Rc<dyn Texture>,
+    channel: usize,
+}
+
+impl Channel {
+    pub fn new(texture: Rc<dyn Texture>, channel: usize) -> Self {
+        assert!(channel < 3, "Channel must be 0, 1 or 2");
+        Self { texture, channel }
+    }
+}
+
+impl Texture for Channel {
+    fn color(&self, u: f64, v: f64, point: Point3) -> Color {
+        let value = self.texture.color(u, v, point)[self.channel];
+        Color::new(value, value, value)
+    }
+
+    fn color_at(&self, rec: &HitRecord) -> Color {
+        let value = self.texture.color_at(rec)[self.channel];
+        Color::new(value, value, value)
+    }
+}
@@ -1,6 +1,7 @@
 use std::rc::Rc;
 
 use super::{solid_color::SolidColor, Texture};
+use crate::shape::HitRecord;
 use crate::vec3::{Color, Point3};
 
 pub struct Checkers {
@@ -19,6 +20,16 @@ impl Checkers {
             even: Rc::new(SolidColor::new(even)),
         }
     }
+
+    fn pick(&self, point: Point3) -> &dyn Texture {
+        let sines =
+            f64::sin(10.0 * point.x()) * f64::sin(10.0 * point.y()) * f64::sin(10.0 * point.z());
+        if sines < 0.0 {
+            self.odd.as_ref()
+        } else {
+            self.even.as_ref()
+        }
+    }
 }
 
 impl Default for Checkers {
@@ -32,12 +43,10 @@ impl Default for Checkers {
 
 impl Texture for Checkers {
     fn color(&self, u: f64, v: f64, point: Point3) -> Color {
-        let sines =
-            f64::sin(10.0 * point.x()) * f64::sin(10.0 * point.y()) * f64::sin(10.0 * point.z());
-        if sines < 0.0 {
-            self.odd.color(u, v, point)
-        } else {
-            self.even.color(u, v, point)
-        }
+        self.pick(point).color(u, v, point)
+    }
+
+    fn color_at(&self, rec: &HitRecord) -> Color {
+        self.pick(rec.point()).color_at(rec)
     }
 }
@@ -1,4 +1,4 @@
-use image::GenericImageView;
+use image::{DynamicImage, GenericImageView};
 
 use super::Texture;
 use crate::vec3::{Color, Point3};
@@ -23,6 +23,11 @@ impl Image {
             }
         };
 
+        Self::from_image(&img)
+    }
+
+    /// An image from one decoded by the `image` crate.
+    pub fn from_image(img: &DynamicImage) -> Self {
         let (width, height) = img.dimensions();
         let (width, height) = (width as usize, height as usize);
 
@@ -37,6 +42,15 @@ impl Image {
             );
         }
 
+        Self::from_pixels(pixels, width, height)
+    }
+
+    /// An image from its pixels, row by row from the top.
+    pub fn from_pixels(pixels: Vec<Color>, width: usize, height: usize) -> Self {
+        assert!(
+            pixels.len() == width * height,
+            "Image needs width * height pixels"
+        );
         Self {
             pixels,
             width,
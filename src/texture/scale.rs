@@ -0,0 +1,31 @@
+use std::rc::Rc;
+
+use super::Texture;
+use crate::shape::HitRecord;
+use crate::vec3::{Color, Point3};
+
+/// Another texture multiplied by a color.
+pub struct Scale {
+    texture: Rc<dyn Texture>,
+    factor: Color,
+}
+
+impl Scale {
+    pub fn new(texture: Rc<dyn Texture>, factor: Color) -> Self {
+        Self { texture, factor }
+    }
+
+    pub fn from_value(texture: Rc<dyn Texture>, factor: f64) -> Self {
+        Self::new(texture, Color::new(factor, factor, factor))
+    }
+}
+
+impl Texture for Scale {
+    fn color(&self, u: f64, v: f64, point: Point3) -> Color {
+        self.factor * self.texture.color(u, v, point)
+    }
+
+    fn color_at(&self, rec: &HitRecord) -> Color {
+        self.factor * self.texture.color_at(rec)
+    }
+}
@@ -16,6 +16,10 @@ impl SolidColor {
             color: Color::new(r, g, b),
         }
     }
+
+    pub fn from_value(value: f64) -> Self {
+        Self::from_rgb(value, value, value)
+    }
 }
 
 impl Default for SolidColor {
@@ -0,0 +1,61 @@
+use std::rc::Rc;
+
+use super::Texture;
+use crate::density::Density;
+use crate::spectrum;
+use crate::vec3::{Color, Point3};
+
+const TABLE_SIZE: usize = 256;
+
+/// Black body emission from a temperature field. The field is scaled so its maximum is
+/// `max_kelvin`, and the radiance follows the Stefan-Boltzmann law, reaching `intensity` times
+/// unit luminance at the hottest point.
+pub struct Temperature {
+    field: Rc<dyn Density>,
+    max_kelvin: f64,
+    intensity: f64,
+    table: Vec<Color>,
+}
+
+impl Temperature {
+    pub fn new(field: Rc<dyn Density>, max_kelvin: f64) -> Self {
+        // Computing the black body color is expensive, so it is tabulated once.
+        let table = (0..TABLE_SIZE)
+            .map(|i| {
+                let fraction = i as f64 / (TABLE_SIZE - 1) as f64;
+                spectrum::blackbody(fraction * max_kelvin) * fraction.powi(4)
+            })
+            .collect();
+        Self {
+            field,
+            max_kelvin,
+            intensity: 1.0,
+            table,
+        }
+    }
+
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn kelvin(&self, point: Point3) -> f64 {
+        let max_density = self.field.max_density();
+        if max_density <= 0.0 {
+            return 0.0;
+        }
+        (self.field.density(point) / max_density).clamp(0.0, 1.0) * self.max_kelvin
+    }
+}
+
+impl Texture for Temperature {
+    fn color(&self, _: f64, _: f64, point: Point3) -> Color {
+        let x = self.kelvin(point) / self.max_kelvin * (TABLE_SIZE - 1) as f64;
+        if x.is_nan() {
+            return Color::default();
+        }
+        let i = usize::min(x as usize, TABLE_SIZE - 2);
+        let t = x - i as f64;
+        (self.table[i] * (1.0 - t) + self.table[i + 1] * t) * self.intensity
+    }
+}
@@ -0,0 +1,25 @@
+use super::Texture;
+use crate::shape::HitRecord;
+use crate::vec3::{Color, Point3};
+
+/// The colors of the vertices of a triangle mesh, interpolated over each triangle by its hits.
+/// Hits without vertex colors get `fallback`.
+pub struct VertexColors {
+    fallback: Color,
+}
+
+impl VertexColors {
+    pub fn new(fallback: Color) -> Self {
+        Self { fallback }
+    }
+}
+
+impl Texture for VertexColors {
+    fn color(&self, _: f64, _: f64, _: Point3) -> Color {
+        self.fallback
+    }
+
+    fn color_at(&self, rec: &HitRecord) -> Color {
+        rec.color().unwrap_or(self.fallback)
+    }
+}
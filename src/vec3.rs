@@ -76,6 +76,17 @@ impl Vec3 {
         }
     }
 
+    pub fn rand_cosine_direction(rng: &mut ThreadRng) -> Self {
+        let r1 = rand(rng);
+        let r2 = rand(rng);
+        let phi = 2.0 * std::f64::consts::PI * r1;
+        Self {
+            x: phi.cos() * r2.sqrt(),
+            y: phi.sin() * r2.sqrt(),
+            z: f64::sqrt(1.0 - r2),
+        }
+    }
+
     pub fn x(&self) -> f64 {
         self.x
     }
@@ -128,6 +139,10 @@ impl Vec3 {
         f64::abs(self.x) < S && f64::abs(self.y) < S && f64::abs(self.z) < S
     }
 
+    pub fn luminance(self) -> f64 {
+        0.2126 * self.x + 0.7152 * self.y + 0.0722 * self.z
+    }
+
     pub fn format_color(self, samples_per_pixel: u32) -> String {
         let mut r = self.x;
         let mut g = self.y;